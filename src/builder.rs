@@ -0,0 +1,126 @@
+use crate::history::History;
+use crate::schdule::{Scheduler, Shared};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use std::collections::HashMap;
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast;
+
+/// fire events buffered for slow [`Scheduler::next_fire`] callers
+const FIRE_CAPACITY: usize = 64;
+
+/// task events buffered for slow [`Scheduler::subscribe`] receivers
+const EVENT_CAPACITY: usize = 256;
+
+/// builder for a [`Scheduler`], created by [`Scheduler::builder`]
+#[derive(Debug, Clone)]
+pub struct SchedulerBuilder {
+    timezone_minutes: i16,
+    chaos_skip_probability: f64,
+    rng_seed: Option<u64>,
+    history: usize,
+    gate: Option<Arc<AtomicBool>>,
+    #[cfg(feature = "tz")]
+    zone: Option<chrono_tz::Tz>,
+}
+
+impl Default for SchedulerBuilder {
+    fn default() -> Self {
+        Self {
+            timezone_minutes: 8 * 60,
+            chaos_skip_probability: 0.0,
+            rng_seed: None,
+            history: 0,
+            gate: None,
+            #[cfg(feature = "tz")]
+            zone: None,
+        }
+    }
+}
+
+impl SchedulerBuilder {
+    /// set the timezone hours and minutes offset, default is +8
+    pub fn timezone(self, timezone_hours: i8, timezone_minutes: i8) -> Self {
+        self.timezone_minutes((timezone_hours as i16) * 60 + (timezone_minutes as i16))
+    }
+
+    /// set the timezone offset in minutes, default is +8 hours
+    pub fn timezone_minutes(mut self, timezone_minutes: i16) -> Self {
+        self.timezone_minutes = timezone_minutes;
+        self
+    }
+
+    /// set an IANA timezone, times of the day follow its daylight saving changes
+    ///
+    /// replaces the fixed offset of [`SchedulerBuilder::timezone_minutes`]
+    #[cfg(feature = "tz")]
+    pub fn zone(mut self, zone: chrono_tz::Tz) -> Self {
+        self.zone = Some(zone);
+        self
+    }
+
+    /// probability in `0.0..=1.0` for each trigger to call `on_skip` instead of `on_time`
+    ///
+    /// meant for chaos testing, default is 0
+    pub fn chaos_skip_probability(mut self, probability: f64) -> Self {
+        self.chaos_skip_probability = probability.clamp(0.0, 1.0);
+        self
+    }
+
+    /// seed the random number generator of the scheduler for reproducible runs
+    ///
+    /// by default it is seeded from the operating system
+    pub fn rng_seed(mut self, seed: u64) -> Self {
+        self.rng_seed = Some(seed);
+        self
+    }
+
+    /// keep the last `capacity` triggers of all tasks, see [`Scheduler::recent_history`]
+    ///
+    /// disabled by default
+    pub fn history(mut self, capacity: usize) -> Self {
+        self.history = capacity;
+        self
+    }
+
+    /// a gate shared by all tasks, triggers call `on_skip` instead of `on_time` while it is
+    /// `false`
+    ///
+    /// e.g. for a maintenance mode, the gate can be opened and closed at any time
+    pub fn gate(mut self, gate: Arc<AtomicBool>) -> Self {
+        self.gate = Some(gate);
+        self
+    }
+
+    /// build the scheduler
+    pub fn build(self) -> Scheduler {
+        let rng = match self.rng_seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_os_rng(),
+        };
+        // the offset of the zone right now, for everything that needs a fixed one
+        #[cfg(feature = "tz")]
+        let timezone_minutes = self.zone.map_or(self.timezone_minutes, |zone| {
+            crate::zone::now(zone).offset().whole_minutes()
+        });
+        #[cfg(not(feature = "tz"))]
+        let timezone_minutes = self.timezone_minutes;
+        Scheduler::from_shared(Shared {
+            timezone_minutes,
+            chaos_skip_probability: self.chaos_skip_probability,
+            rng: Mutex::new(rng),
+            history: (self.history > 0).then(|| History::new(self.history)),
+            gate: self.gate,
+            maintenance: Mutex::new(Vec::new()),
+            flights: Mutex::new(HashMap::new()),
+            #[cfg(feature = "tz")]
+            zone: self.zone,
+            fires: broadcast::channel(FIRE_CAPACITY).0,
+            events: broadcast::channel(EVENT_CAPACITY).0,
+            lifecycle: Mutex::new(Vec::new()),
+            #[cfg(feature = "prometheus")]
+            metrics: crate::metrics::Metrics::new(),
+        })
+    }
+}
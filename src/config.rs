@@ -0,0 +1,50 @@
+use crate::task::{Notifiable, Task};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Config {
+    tasks: Vec<Entry>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Entry {
+    schedule: String,
+    handler: String,
+}
+
+/// parse a JSON config and build a task for each entry with `factory`
+///
+/// every bad entry is reported, with its index and handler key, so nothing is built
+/// unless the whole config is valid
+pub(crate) fn build<F>(
+    config: &str,
+    timezone_minutes: i16,
+    mut factory: F,
+) -> Result<Vec<Box<dyn Notifiable>>, String>
+where
+    F: FnMut(&str, Task) -> Option<Box<dyn Notifiable>>,
+{
+    let config: Config =
+        serde_json::from_str(config).map_err(|e| format!("Invalid config: {e}"))?;
+
+    let mut tasks = Vec::with_capacity(config.tasks.len());
+    let mut errors = Vec::new();
+    for (index, entry) in config.tasks.iter().enumerate() {
+        let context = format!("task {index} (handler '{}')", entry.handler);
+        match Task::parse_with_timezone_minutes(&entry.schedule, timezone_minutes) {
+            Ok(task) => match factory(&entry.handler, task) {
+                Some(task) => tasks.push(task),
+                None => errors.push(format!("{context}: unknown handler")),
+            },
+            Err(e) => errors.push(format!("{context}: {e}")),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(tasks)
+    } else {
+        Err(errors.join("; "))
+    }
+}
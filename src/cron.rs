@@ -0,0 +1,177 @@
+use time::{Date, Month, OffsetDateTime, Time};
+
+/// years searched for a match before giving up, e.g. for `0 0 30 2 *`
+const MAX_YEARS: i32 = 5;
+
+/// a parsed cron expression
+///
+/// five fields `minute hour day-of-month month day-of-week`, or six with a leading
+/// `second` field. Each field is `*`, a number, a range `a-b`, a step `*/n`, `a/n` or
+/// `a-b/n`, or a comma separated list of those. Day of week is 0-7 with both 0 and 7
+/// for Sunday. When both day fields are restricted a day matching either one fires,
+/// like in standard cron.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct CronExpr {
+    seconds: Vec<bool>,
+    minutes: Vec<bool>,
+    hours: Vec<bool>,
+    days_of_month: Vec<bool>,
+    months: Vec<bool>,
+    days_of_week: Vec<bool>,
+    day_of_month_any: bool,
+    day_of_week_any: bool,
+}
+
+impl CronExpr {
+    pub(crate) fn parse(expr: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        let (second, rest) = match fields.len() {
+            5 => ("0", &fields[..]),
+            6 => (fields[0], &fields[1..]),
+            n => {
+                return Err(format!(
+                    "Invalid cron expression '{expr}': expected 5 or 6 fields, got {n}"
+                ));
+            }
+        };
+
+        let mut days_of_week = parse_field(rest[4], "day of week", 0, 7)?;
+        if days_of_week[7] {
+            days_of_week[0] = true;
+        }
+        days_of_week.truncate(7);
+
+        Ok(Self {
+            seconds: parse_field(second, "second", 0, 59)?,
+            minutes: parse_field(rest[0], "minute", 0, 59)?,
+            hours: parse_field(rest[1], "hour", 0, 23)?,
+            days_of_month: parse_field(rest[2], "day of month", 1, 31)?,
+            months: parse_field(rest[3], "month", 1, 12)?,
+            days_of_week,
+            day_of_month_any: rest[2] == "*",
+            day_of_week_any: rest[4] == "*",
+        })
+    }
+
+    /// the first matching time at or after `from`
+    pub(crate) fn next_from(&self, from: OffsetDateTime) -> Option<OffsetDateTime> {
+        let offset = from.offset();
+        let mut date = from.date();
+        let mut time = from.time();
+        if time.nanosecond() > 0 {
+            // round up to the next whole second
+            let next = from.replace_nanosecond(0).ok()? + time::Duration::SECOND;
+            date = next.date();
+            time = next.time();
+        }
+        let last_year = date.year() + MAX_YEARS;
+
+        while date.year() <= last_year {
+            if !self.months[date.month() as usize - 1] {
+                let (year, month) = match date.month() {
+                    Month::December => (date.year() + 1, Month::January),
+                    month => (date.year(), month.next()),
+                };
+                date = Date::from_calendar_date(year, month, 1).ok()?;
+                time = Time::MIDNIGHT;
+                continue;
+            }
+            if !self.matches_day(date) {
+                date = date.next_day()?;
+                time = Time::MIDNIGHT;
+                continue;
+            }
+            if let Some(time) = self.time_from(time) {
+                return Some(date.with_time(time).assume_offset(offset));
+            }
+            date = date.next_day()?;
+            time = Time::MIDNIGHT;
+        }
+        None
+    }
+
+    fn matches_day(&self, date: Date) -> bool {
+        let day_of_month = self.days_of_month[date.day() as usize - 1];
+        let day_of_week = self.days_of_week[date.weekday().number_days_from_sunday() as usize];
+        match (self.day_of_month_any, self.day_of_week_any) {
+            (true, true) => true,
+            (false, true) => day_of_month,
+            (true, false) => day_of_week,
+            (false, false) => day_of_month || day_of_week,
+        }
+    }
+
+    /// the first matching time of day at or after `from`
+    fn time_from(&self, from: Time) -> Option<Time> {
+        for hour in from.hour() as usize..24 {
+            if !self.hours[hour] {
+                continue;
+            }
+            let first_minute = if hour == from.hour() as usize {
+                from.minute() as usize
+            } else {
+                0
+            };
+            for minute in first_minute..60 {
+                if !self.minutes[minute] {
+                    continue;
+                }
+                let first_second =
+                    if hour == from.hour() as usize && minute == from.minute() as usize {
+                        from.second() as usize
+                    } else {
+                        0
+                    };
+                if let Some(second) = (first_second..60).find(|second| self.seconds[*second]) {
+                    return Time::from_hms(hour as u8, minute as u8, second as u8).ok();
+                }
+            }
+        }
+        None
+    }
+}
+
+/// parse a field into the set of matching values, indexed from `min`
+fn parse_field(field: &str, name: &str, min: u32, max: u32) -> Result<Vec<bool>, String> {
+    let mut values = vec![false; (max - min + 1) as usize];
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step = step
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|step| *step > 0)
+                    .ok_or_else(|| format!("Invalid {name} step '{step}' in '{field}'"))?;
+                (range, step)
+            }
+            None => (part, 1),
+        };
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (
+                parse_value(start, name, min, max)?,
+                parse_value(end, name, min, max)?,
+            )
+        } else {
+            let start = parse_value(range, name, min, max)?;
+            // `a/n` runs from a to the end of the field
+            (start, if part.contains('/') { max } else { start })
+        };
+        if start > end {
+            return Err(format!("Invalid {name} range '{range}' in '{field}'"));
+        }
+        for value in (start..=end).step_by(step as usize) {
+            values[(value - min) as usize] = true;
+        }
+    }
+    Ok(values)
+}
+
+fn parse_value(value: &str, name: &str, min: u32, max: u32) -> Result<u32, String> {
+    value
+        .parse::<u32>()
+        .ok()
+        .filter(|value| (min..=max).contains(value))
+        .ok_or_else(|| format!("Invalid {name} '{value}', expected {min}-{max}"))
+}
@@ -0,0 +1,19 @@
+use crate::task::SkipReason;
+use time::OffsetDateTime;
+
+/// a step in the lifecycle of a task, see [`Scheduler::subscribe`](crate::Scheduler::subscribe)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TaskEvent {
+    /// the task was started
+    Started { id: u64, name: String },
+    /// the handler of the task returned, failed or not
+    Fired {
+        id: u64,
+        /// time of the trigger, in the scheduler timezone
+        at: OffsetDateTime,
+    },
+    /// a trigger was skipped
+    Skipped { id: u64, reason: SkipReason },
+    /// the task stopped running, cancelled or out of triggers
+    Stopped { id: u64 },
+}
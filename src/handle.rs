@@ -0,0 +1,175 @@
+use crate::history::Outcome;
+use crate::task::{AtBehavior, SkipReason, Task};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use time::OffsetDateTime;
+use tokio_util::sync::CancellationToken;
+
+/// state shared between a spawned task loop and its handle
+#[derive(Debug)]
+pub(crate) struct TaskState {
+    pub(crate) id: u64,
+    pub(crate) name: String,
+    /// the current schedule, replaced by [`Scheduler::reschedule`](crate::Scheduler::reschedule)
+    pub(crate) task: Mutex<Arc<Task>>,
+    /// held while a handler runs, so a reschedule never stops a loop in the middle of one
+    pub(crate) firing: tokio::sync::Mutex<()>,
+    pub(crate) created_at: OffsetDateTime,
+    pub(crate) cancel: CancellationToken,
+    pub(crate) next_run: Mutex<Option<OffsetDateTime>>,
+    /// number of times the handler has been called, skips excluded
+    pub(crate) fires: AtomicU64,
+    /// cancel the task once it has fired this many times
+    pub(crate) max_fires: Option<u64>,
+    /// triggers left to skip, see [`Notifiable::skip_first`](crate::Notifiable::skip_first)
+    pub(crate) warmup: AtomicU64,
+    pub(crate) at_behavior: AtBehavior,
+    /// upper bound of the random delay of each trigger
+    pub(crate) max_jitter: Duration,
+    pub(crate) metrics: Mutex<TaskMetrics>,
+}
+
+impl TaskState {
+    /// the current schedule
+    pub(crate) fn task(&self) -> Task {
+        Task::clone(&self.task.lock().unwrap())
+    }
+
+    /// record the next time the loop is going to fire
+    pub(crate) fn set_next_run(&self, next: Option<OffsetDateTime>) {
+        *self.next_run.lock().unwrap() = next;
+    }
+
+    /// use up one of the warmup skips, if any are left
+    pub(crate) fn take_warmup(&self) -> Option<SkipReason> {
+        self.warmup
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |left| {
+                left.checked_sub(1)
+            })
+            .is_ok()
+            .then_some(SkipReason::Warmup)
+    }
+
+    /// record how late the loop woke up for a trigger
+    pub(crate) fn record_drift(&self, drift: Duration) {
+        self.metrics.lock().unwrap().record_drift(drift);
+    }
+
+    /// record the outcome of a trigger at `at`
+    pub(crate) fn record_outcome(&self, outcome: Outcome, at: OffsetDateTime) {
+        let mut metrics = self.metrics.lock().unwrap();
+        match outcome {
+            Outcome::Fired | Outcome::Failed => metrics.last_run = Some(at),
+            Outcome::Skipped => metrics.skips += 1,
+        }
+    }
+
+    /// count a call of the handler, cancelling the task once `max_fires` is reached
+    pub(crate) fn count_fire(&self) {
+        let fires = self.fires.fetch_add(1, Ordering::Relaxed) + 1;
+        if self.max_fires.is_some_and(|max| fires >= max) {
+            self.cancel.cancel();
+        }
+    }
+}
+
+/// running statistics of a task loop
+#[derive(Debug, Default)]
+pub(crate) struct TaskMetrics {
+    drift: Option<DriftStats>,
+    drift_total: Duration,
+    /// time of the last call of the handler
+    pub(crate) last_run: Option<OffsetDateTime>,
+    /// number of skipped triggers
+    pub(crate) skips: u64,
+}
+
+impl TaskMetrics {
+    fn record_drift(&mut self, drift: Duration) {
+        self.drift_total += drift;
+        let stats = self.drift.get_or_insert(DriftStats {
+            count: 0,
+            min: drift,
+            max: drift,
+            mean: drift,
+        });
+        stats.count += 1;
+        stats.min = stats.min.min(drift);
+        stats.max = stats.max.max(drift);
+        stats.mean = self.drift_total.div_f64(stats.count as f64);
+    }
+}
+
+/// how late the triggers of a task woke up compared to their scheduled time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DriftStats {
+    /// number of triggers measured
+    pub count: u64,
+    pub min: Duration,
+    pub max: Duration,
+    pub mean: Duration,
+}
+
+/// a handle to a task started by [`Scheduler::run`](crate::Scheduler::run)
+///
+/// dropping the handle does not stop the task
+#[derive(Debug, Clone)]
+pub struct TaskHandle {
+    pub(crate) state: Arc<TaskState>,
+}
+
+impl TaskHandle {
+    /// the id of the task, unique within its scheduler
+    pub fn id(&self) -> u64 {
+        self.state.id
+    }
+
+    /// the name of the task, see [`Notifiable::name`](crate::Notifiable::name)
+    pub fn name(&self) -> &str {
+        &self.state.name
+    }
+
+    /// the schedule the task runs on, the one it was created from unless rescheduled
+    ///
+    /// shared rather than borrowed, since a reschedule swaps in a new one, deref it for a
+    /// `&Task`
+    pub fn task(&self) -> Arc<Task> {
+        self.state.task.lock().unwrap().clone()
+    }
+
+    /// the time the task was registered, in the scheduler timezone
+    pub fn created_at(&self) -> OffsetDateTime {
+        self.state.created_at
+    }
+
+    /// the next time the task is going to fire
+    ///
+    /// returns `None` once the task is cancelled or has nothing left to run
+    pub fn next_run(&self) -> Option<OffsetDateTime> {
+        if self.is_cancelled() {
+            return None;
+        }
+        *self.state.next_run.lock().unwrap()
+    }
+
+    /// cancel this task only, other tasks of the scheduler keep running
+    pub fn cancel(&self) {
+        self.state.cancel.cancel();
+    }
+
+    /// what the task does when started after its time of the day, only used by `At` tasks
+    pub fn at_behavior(&self) -> AtBehavior {
+        self.state.at_behavior
+    }
+
+    /// drift statistics of the triggers so far, `None` before the first trigger
+    pub fn drift_stats(&self) -> Option<DriftStats> {
+        self.state.metrics.lock().unwrap().drift
+    }
+
+    /// check if the task has been cancelled, either by itself or by the scheduler
+    pub fn is_cancelled(&self) -> bool {
+        self.state.cancel.is_cancelled()
+    }
+}
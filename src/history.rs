@@ -0,0 +1,58 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use time::OffsetDateTime;
+
+/// what happened when a task was triggered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// `on_time` ran successfully
+    Fired,
+    /// `on_time_fallible` returned an error
+    Failed,
+    /// the trigger was skipped and `on_skip` ran
+    Skipped,
+}
+
+/// an entry of the execution history, see [`SchedulerBuilder::history`](crate::SchedulerBuilder::history)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryEntry {
+    /// id of the task handle
+    pub id: u64,
+    /// name of the task
+    pub name: String,
+    /// time of the trigger, in the scheduler timezone
+    pub at: OffsetDateTime,
+    pub outcome: Outcome,
+}
+
+/// ring buffer keeping the most recent entries
+#[derive(Debug)]
+pub(crate) struct History {
+    capacity: usize,
+    entries: Mutex<VecDeque<HistoryEntry>>,
+}
+
+impl History {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    pub(crate) fn record(&self, entry: HistoryEntry) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() == self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back(entry);
+    }
+
+    /// entries from oldest to newest
+    pub(crate) fn entries(&self) -> Vec<HistoryEntry> {
+        self.entries.lock().unwrap().iter().cloned().collect()
+    }
+}
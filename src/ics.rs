@@ -0,0 +1,116 @@
+use crate::handle::TaskState;
+use crate::task::{RunTimes, Task};
+use std::fmt::Write;
+use time::{OffsetDateTime, UtcOffset, macros::format_description};
+
+/// upper bound of events enumerated for a single task
+const MAX_EVENTS: usize = 1000;
+
+/// render the runs of `tasks` between `now` and `until` as an iCalendar document
+///
+/// each task comes with the time of its next run, as tracked by its loop. Skip rules
+/// are evaluated on `local` of each run, the time in the scheduler timezone
+pub(crate) fn render(
+    tasks: &[(&TaskState, Option<OffsetDateTime>)],
+    now: OffsetDateTime,
+    until: OffsetDateTime,
+    local: impl Fn(OffsetDateTime) -> OffsetDateTime,
+) -> String {
+    let mut out = String::new();
+    line(&mut out, "BEGIN:VCALENDAR");
+    line(&mut out, "VERSION:2.0");
+    line(&mut out, "PRODID:-//easy-schedule//EN");
+
+    for (state, first) in tasks {
+        let Some(first) = *first else {
+            continue;
+        };
+        if first > until {
+            continue;
+        }
+
+        let task = state.task();
+        if let Some(rule) = rrule(&task, until) {
+            event(&mut out, state, 0, first, now, Some(&rule));
+            continue;
+        }
+
+        let runs = RunTimes::new(&task, Some(first))
+            .take_while(|time| *time <= until)
+            .filter(|time| !task.is_skipped(local(*time)))
+            .take(MAX_EVENTS);
+        for (index, time) in runs.enumerate() {
+            event(&mut out, state, index, time, now, None);
+        }
+    }
+
+    line(&mut out, "END:VCALENDAR");
+    out
+}
+
+/// the recurrence rule of a task, if it can be expressed as one
+fn rrule(task: &Task, until: OffsetDateTime) -> Option<String> {
+    if !task.skips().is_empty() {
+        return None;
+    }
+    let until = timestamp(until);
+    match task {
+        Task::Interval(interval, _) | Task::FixedRate(interval, _) => {
+            Some(format!("FREQ=SECONDLY;INTERVAL={interval};UNTIL={until}"))
+        }
+        Task::At(..) => Some(format!("FREQ=DAILY;UNTIL={until}")),
+        _ => None,
+    }
+}
+
+fn event(
+    out: &mut String,
+    state: &TaskState,
+    index: usize,
+    start: OffsetDateTime,
+    now: OffsetDateTime,
+    rule: Option<&str>,
+) {
+    line(out, "BEGIN:VEVENT");
+    line(out, &format!("UID:{}-{index}@easy-schedule", state.id));
+    line(out, &format!("DTSTAMP:{}", timestamp(now)));
+    line(out, &format!("DTSTART:{}", timestamp(start)));
+    if let Some(rule) = rule {
+        line(out, &format!("RRULE:{rule}"));
+    }
+    line(out, &format!("SUMMARY:{}", escape(&state.name)));
+    line(
+        out,
+        &format!("DESCRIPTION:{}", escape(&state.task().to_string())),
+    );
+    line(out, "END:VEVENT");
+}
+
+/// format a time as an ICS UTC timestamp, like `20241225T090000Z`
+fn timestamp(time: OffsetDateTime) -> String {
+    let format = format_description!("[year][month][day]T[hour][minute][second]Z");
+    time.to_offset(UtcOffset::UTC)
+        .format(&format)
+        .unwrap_or_default()
+}
+
+/// escape a text value
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.trim().chars() {
+        match c {
+            '\\' | ';' | ',' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' => escaped.push_str("\\n"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// content lines are terminated by CRLF
+fn line(out: &mut String, content: &str) {
+    let _ = write!(out, "{content}\r\n");
+}
@@ -1,14 +1,46 @@
+mod builder;
+#[cfg(feature = "serde")]
+mod config;
+mod cron;
+mod event;
+mod handle;
+mod history;
+mod ics;
+#[cfg(feature = "prometheus")]
+mod metrics;
+mod producer;
 mod schdule;
+mod skip_expr;
 mod task;
+#[cfg(feature = "tz")]
+mod zone;
 
 pub mod prelude {
+    pub use crate::builder::SchedulerBuilder;
+    pub use crate::event::TaskEvent;
+    pub use crate::handle::{DriftStats, TaskHandle};
+    pub use crate::history::{HistoryEntry, Outcome};
+    pub use crate::producer::Producer;
     pub use crate::schdule::Scheduler;
-    pub use crate::task::{Notifiable, Skip, Task};
+    pub use crate::task::{
+        AtBehavior, CircuitBreaker, DailyTime, FireOutcome, Notifiable, PanicAction, RunTimes,
+        Skip, SkipReason, Task, TaskBuilder, TaskError, collides_within,
+    };
     pub use async_trait::async_trait;
     pub use tokio_util::sync::CancellationToken;
 }
 
 pub use crate::{
-    prelude::{Notifiable, Scheduler},
-    task::{Skip, Task},
+    event::TaskEvent,
+    handle::DriftStats,
+    history::{HistoryEntry, Outcome},
+    prelude::{Notifiable, Scheduler, SchedulerBuilder, TaskHandle},
+    producer::Producer,
+    task::{
+        AtBehavior, CircuitBreaker, DailyTime, FireOutcome, PanicAction, RunTimes, Skip,
+        SkipReason, Task, TaskBuilder, TaskError, collides_within,
+    },
 };
+
+#[cfg(feature = "tz")]
+pub use chrono_tz::Tz;
@@ -0,0 +1,81 @@
+use crate::history::Outcome;
+use prometheus::{IntCounterVec, IntGaugeVec, Opts, Registry};
+
+/// prometheus metrics of a scheduler, labeled by task name
+#[derive(Debug)]
+pub(crate) struct Metrics {
+    registry: Registry,
+    executions: IntCounterVec,
+    skips: IntCounterVec,
+    active: IntGaugeVec,
+}
+
+impl Metrics {
+    pub(crate) fn new() -> Self {
+        let executions = IntCounterVec::new(
+            Opts::new(
+                "easy_schedule_executions_total",
+                "number of times a task handler was called",
+            ),
+            &["task"],
+        )
+        .expect("valid metric");
+        let skips = IntCounterVec::new(
+            Opts::new(
+                "easy_schedule_skips_total",
+                "number of skipped task triggers",
+            ),
+            &["task"],
+        )
+        .expect("valid metric");
+        let active = IntGaugeVec::new(
+            Opts::new("easy_schedule_active_tasks", "number of running task loops"),
+            &["task"],
+        )
+        .expect("valid metric");
+
+        let registry = Registry::new();
+        for collector in [
+            Box::new(executions.clone()) as Box<dyn prometheus::core::Collector>,
+            Box::new(skips.clone()),
+            Box::new(active.clone()),
+        ] {
+            registry.register(collector).expect("unique metric");
+        }
+
+        Self {
+            registry,
+            executions,
+            skips,
+            active,
+        }
+    }
+
+    pub(crate) fn registry(&self) -> &Registry {
+        &self.registry
+    }
+
+    /// count a trigger of the task
+    pub(crate) fn record(&self, name: &str, outcome: Outcome) {
+        match outcome {
+            Outcome::Fired | Outcome::Failed => self.executions.with_label_values(&[name]).inc(),
+            Outcome::Skipped => self.skips.with_label_values(&[name]).inc(),
+        }
+    }
+
+    /// count a running task loop until the guard is dropped
+    pub(crate) fn active(&self, name: &str) -> ActiveGuard {
+        let gauge = self.active.with_label_values(&[name]);
+        gauge.inc();
+        ActiveGuard(gauge)
+    }
+}
+
+/// decrements the active tasks gauge when dropped
+pub(crate) struct ActiveGuard(prometheus::IntGauge);
+
+impl Drop for ActiveGuard {
+    fn drop(&mut self) {
+        self.0.dec();
+    }
+}
@@ -0,0 +1,123 @@
+use crate::task::{
+    AtBehavior, CircuitBreaker, Notifiable, PanicAction, SkipReason, Task, TaskError,
+};
+use async_trait::async_trait;
+use std::fmt::Debug;
+use std::ops::RangeInclusive;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+/// a task that produces a value on each trigger, see
+/// [`Scheduler::run_collecting`](crate::Scheduler::run_collecting)
+///
+/// the schedule, the name and every other hook come from [`Notifiable`], `produce` is
+/// called instead of `on_time`
+#[async_trait]
+pub trait Producer: Notifiable {
+    /// the value sent for each trigger
+    type Output: Send + 'static;
+
+    /// called when the task is scheduled
+    async fn produce(&self, cancel: CancellationToken) -> Self::Output;
+}
+
+/// runs a [`Producer`] as a [`Notifiable`], sending its values over a channel
+pub(crate) struct Collecting<T: Producer> {
+    pub(crate) task: T,
+    pub(crate) tx: mpsc::Sender<T::Output>,
+}
+
+impl<T: Producer> Debug for Collecting<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Collecting").field(&self.task).finish()
+    }
+}
+
+#[async_trait]
+impl<T: Producer> Notifiable for Collecting<T> {
+    fn get_task(&self) -> Task {
+        self.task.get_task()
+    }
+
+    fn name(&self) -> String {
+        self.task.name()
+    }
+
+    async fn on_time_adaptive(
+        &self,
+        cancel: CancellationToken,
+    ) -> Result<Option<Duration>, TaskError> {
+        let output = self.task.produce(cancel.clone()).await;
+        if self.tx.send(output).await.is_err() {
+            // nobody is collecting anymore
+            tracing::debug!("receiver dropped, cancel task");
+            cancel.cancel();
+        }
+        Ok(None)
+    }
+
+    fn single_flight_key(&self) -> Option<String> {
+        self.task.single_flight_key()
+    }
+
+    async fn on_error(&self, err: &TaskError, cancel: CancellationToken) {
+        self.task.on_error(err, cancel).await;
+    }
+
+    async fn on_panic(&self, message: &str, cancel: CancellationToken) -> PanicAction {
+        self.task.on_panic(message, cancel).await
+    }
+
+    fn delay_bounds(&self) -> RangeInclusive<Duration> {
+        self.task.delay_bounds()
+    }
+
+    async fn on_skip(&self, cancel: CancellationToken) {
+        self.task.on_skip(cancel).await;
+    }
+
+    async fn on_skip_with_reason(&self, cancel: CancellationToken, reason: SkipReason) {
+        self.task.on_skip_with_reason(cancel, reason).await;
+    }
+
+    fn skip_first(&self) -> u64 {
+        self.task.skip_first()
+    }
+
+    fn skip_if_running(&self) -> bool {
+        self.task.skip_if_running()
+    }
+
+    fn count_successes_only(&self) -> bool {
+        self.task.count_successes_only()
+    }
+
+    fn at_behavior(&self) -> AtBehavior {
+        self.task.at_behavior()
+    }
+
+    fn align_interval(&self) -> bool {
+        self.task.align_interval()
+    }
+
+    fn staleness(&self) -> Option<Duration> {
+        self.task.staleness()
+    }
+
+    fn retry_until_first_success(&self) -> Option<Duration> {
+        self.task.retry_until_first_success()
+    }
+
+    fn circuit_breaker(&self) -> Option<CircuitBreaker> {
+        self.task.circuit_breaker()
+    }
+
+    async fn on_circuit_open(&self, cancel: CancellationToken) {
+        self.task.on_circuit_open(cancel).await;
+    }
+
+    async fn on_circuit_close(&self, cancel: CancellationToken) {
+        self.task.on_circuit_close(cancel).await;
+    }
+}
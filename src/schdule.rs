@@ -1,13 +1,266 @@
-use crate::task::{Notifiable, Task, get_next_time, get_now};
-use time::OffsetDateTime;
+use crate::builder::SchedulerBuilder;
+#[cfg(feature = "serde")]
+use crate::config;
+use crate::cron::CronExpr;
+use crate::event::TaskEvent;
+use crate::handle::{TaskHandle, TaskState};
+use crate::history::{History, HistoryEntry, Outcome};
+use crate::ics;
+#[cfg(feature = "prometheus")]
+use crate::metrics::Metrics;
+use crate::producer::{Collecting, Producer};
+use crate::task::{
+    AtBehavior, FireOutcome, Notifiable, PanicAction, RunTimes, Task, TaskError, get_next_time,
+    get_now,
+};
+use crate::task::{Skip, SkipReason};
+use futures_util::FutureExt;
+use rand::Rng;
+use rand::rngs::StdRng;
+use std::any::Any;
+use std::collections::{BTreeMap, HashMap};
+use std::panic::AssertUnwindSafe;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+#[cfg(feature = "serde")]
+use time::format_description::well_known::Rfc3339;
+use time::{Date, OffsetDateTime, UtcOffset};
 use tokio::select;
-use tokio::time::{Duration, Instant, sleep, sleep_until};
+use tokio::sync::{broadcast, mpsc};
+use tokio::task::JoinHandle;
+use tokio::time::{Duration, Instant, MissedTickBehavior, sleep_until};
 use tokio_util::sync::CancellationToken;
 use tracing::instrument;
 
+/// how far ahead [`Scheduler::detect_clustering`] looks
+const CLUSTER_HORIZON: time::Duration = time::Duration::DAY;
+
+/// upper bound of skipped runs passed over by [`Scheduler::next_run_times`]
+const MAX_SKIPPED_RUNS: usize = 24 * 366;
+
+/// upper bound of runs enumerated for a single task when looking for clusters
+const MAX_CLUSTER_RUNS: usize = 1000;
+
+/// upper bound of triggers returned by [`Scheduler::simulate`]
+const MAX_SIMULATED_RUNS: usize = 10_000;
+
+/// upper bound of a single delay, about 30 years, so huge delays, delay hints, cooldowns
+/// and jitters do not overflow
+pub(crate) const MAX_DELAY: Duration = Duration::from_secs(30 * 365 * 24 * 3600);
+
+/// the instant `delay` after `start`, clamped to [`MAX_DELAY`]
+fn after(start: Instant, delay: Duration) -> Instant {
+    start + delay.min(MAX_DELAY)
+}
+
 pub struct Scheduler {
     cancel: CancellationToken,
-    timezone_minutes: i16,
+    shared: Arc<Shared>,
+    next_id: AtomicU64,
+    /// tasks started by this scheduler, removed once their loop ends
+    tasks: Arc<Mutex<BTreeMap<u64, Arc<TaskState>>>>,
+    joins: Mutex<Vec<(Arc<TaskState>, JoinHandle<()>)>>,
+    /// loops of running tasks, removed once a loop ends
+    loops: Arc<Mutex<HashMap<u64, Loop>>>,
+    active: Arc<AtomicUsize>,
+}
+
+/// the future of a task loop
+type Run = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// what a task waits for before its loop starts
+struct StartWhen {
+    /// resolves once the loop may start, `false` to never start it
+    ready: Pin<Box<dyn Future<Output = bool> + Send>>,
+    /// when the loop starts, if already known, to report the first run meanwhile
+    at: Option<OffsetDateTime>,
+}
+
+/// the loop of a task, kept to restart it with a new schedule, see [`Scheduler::reschedule`]
+struct Loop {
+    /// builds a loop on the current schedule of the task
+    start: Arc<dyn Fn(Arc<TaskState>) -> Run + Send + Sync>,
+    /// stops the running loop without cancelling the task
+    stop: CancellationToken,
+}
+
+/// counts a running task loop until dropped, so aborted loops are counted out as well
+struct ActiveCount(Arc<AtomicUsize>);
+
+impl ActiveCount {
+    fn new(active: &Arc<AtomicUsize>) -> Self {
+        active.fetch_add(1, Ordering::Relaxed);
+        Self(active.clone())
+    }
+}
+
+impl Drop for ActiveCount {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// scheduler settings and state shared with the task loops
+#[derive(Debug)]
+pub(crate) struct Shared {
+    pub(crate) timezone_minutes: i16,
+    pub(crate) chaos_skip_probability: f64,
+    pub(crate) rng: Mutex<StdRng>,
+    pub(crate) history: Option<History>,
+    pub(crate) gate: Option<Arc<AtomicBool>>,
+    /// cron starts and lengths of the maintenance windows, see
+    /// [`Scheduler::add_maintenance_window`]
+    pub(crate) maintenance: Mutex<Vec<(CronExpr, time::Duration)>>,
+    /// locks of the single flight keys, see [`Notifiable::single_flight_key`]
+    pub(crate) flights: Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>,
+    #[cfg(feature = "tz")]
+    pub(crate) zone: Option<chrono_tz::Tz>,
+    /// names of the fired tasks, see [`Scheduler::next_fire`]
+    pub(crate) fires: broadcast::Sender<String>,
+    /// lifecycle events of the tasks, see [`Scheduler::subscribe`]
+    pub(crate) events: broadcast::Sender<TaskEvent>,
+    /// channels receiving every task event, and whether sending waits for room, see
+    /// [`Scheduler::lifecycle_channel`]
+    pub(crate) lifecycle: Mutex<Vec<(mpsc::Sender<TaskEvent>, bool)>>,
+    #[cfg(feature = "prometheus")]
+    pub(crate) metrics: Metrics,
+}
+
+impl Shared {
+    /// send a task event to the subscribers and the lifecycle channels, no subscriber is
+    /// not an error
+    async fn emit(&self, event: TaskEvent) {
+        let channels = {
+            let mut channels = self.lifecycle.lock().unwrap();
+            channels.retain(|(tx, _)| !tx.is_closed());
+            channels.clone()
+        };
+        for (tx, wait) in channels {
+            if wait {
+                let _ = tx.send(event.clone()).await;
+            } else if tx.try_send(event.clone()).is_err() {
+                tracing::debug!(?event, "lifecycle channel full, event dropped");
+            }
+        }
+        let _ = self.events.send(event);
+    }
+
+    /// the current time in the scheduler timezone
+    fn now(&self) -> OffsetDateTime {
+        #[cfg(feature = "tz")]
+        if let Some(zone) = self.zone {
+            return crate::zone::now(zone);
+        }
+        get_now(self.timezone_minutes).unwrap_or_else(|_| OffsetDateTime::now_utc())
+    }
+
+    /// move a time of the day of `schedule` to the offset the timezone has on its date
+    ///
+    /// a no-op for fixed offsets and for tasks not tied to the time of the day
+    fn wall_clock(&self, schedule: &Task, time: OffsetDateTime) -> OffsetDateTime {
+        #[cfg(feature = "tz")]
+        if let Some(zone) = self.zone
+            && schedule.follows_wall_clock()
+        {
+            return crate::zone::localize(zone, time);
+        }
+        let _ = schedule;
+        time
+    }
+
+    /// `time` in the offset of the scheduler timezone, skip rules read dates and times
+    /// of the day in it whatever the offset of the task
+    fn local(&self, time: OffsetDateTime) -> OffsetDateTime {
+        #[cfg(feature = "tz")]
+        if let Some(zone) = self.zone {
+            return time.to_offset(crate::zone::offset_at(zone, time));
+        }
+        UtcOffset::from_whole_seconds(self.timezone_minutes as i32 * 60)
+            .map_or(time, |offset| time.to_offset(offset))
+    }
+
+    /// check if a trigger at `time` matches one of the skip rules
+    fn rule_skip(&self, skip: Option<&[Skip]>, time: OffsetDateTime) -> bool {
+        let time = self.local(time);
+        skip.is_some_and(|skip| skip.iter().any(|s| s.is_skip(time)))
+    }
+
+    /// check if a trigger at `time` is skipped, by one of the rules, the gate or by chaos
+    fn skip_reason(&self, skip: Option<&[Skip]>, time: OffsetDateTime) -> Option<SkipReason> {
+        if self.rule_skip(skip, time) {
+            return Some(SkipReason::Rule);
+        }
+        self.scheduler_skip()
+    }
+
+    /// check if a trigger is skipped by the scheduler, whatever the task
+    fn scheduler_skip(&self) -> Option<SkipReason> {
+        if self
+            .gate
+            .as_ref()
+            .is_some_and(|gate| !gate.load(Ordering::Relaxed))
+        {
+            return Some(SkipReason::Gate);
+        }
+        if self.in_maintenance(self.now()) {
+            return Some(SkipReason::Gate);
+        }
+        self.chaos_skip().then_some(SkipReason::Chaos)
+    }
+
+    /// add a trigger of the task to the history, if enabled
+    fn record(&self, state: &TaskState, outcome: Outcome) {
+        #[cfg(feature = "prometheus")]
+        self.metrics.record(&state.name, outcome);
+        let at = self.now();
+        state.record_outcome(outcome, at);
+        if let Some(history) = &self.history {
+            history.record(HistoryEntry {
+                id: state.id,
+                name: state.name.clone(),
+                at,
+                outcome,
+            });
+        }
+    }
+
+    /// check if `now` is in one of the maintenance windows
+    fn in_maintenance(&self, now: OffsetDateTime) -> bool {
+        self.maintenance
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|(cron, length)| {
+                // a window started after `now - length` is still open
+                cron.next_from(now - *length + time::Duration::NANOSECOND)
+                    .is_some_and(|start| start <= now)
+            })
+    }
+
+    /// the lock of a single flight key, created on first use
+    fn flight(&self, key: String) -> Arc<tokio::sync::Mutex<()>> {
+        self.flights.lock().unwrap().entry(key).or_default().clone()
+    }
+
+    /// a random delay in `0..=max` for a trigger
+    fn jitter(&self, max: Duration) -> Duration {
+        if max.is_zero() {
+            return Duration::ZERO;
+        }
+        let max = u64::try_from(max.as_nanos()).unwrap_or(u64::MAX);
+        Duration::from_nanos(self.rng.lock().unwrap().random_range(0..=max))
+    }
+
+    /// randomly skip a trigger with the configured chaos probability
+    fn chaos_skip(&self) -> bool {
+        self.chaos_skip_probability > 0.0
+            && self
+                .rng
+                .lock()
+                .unwrap()
+                .random_bool(self.chaos_skip_probability)
+    }
 }
 
 impl Default for Scheduler {
@@ -24,45 +277,712 @@ impl Scheduler {
 
     /// create a new scheduler with specified timezone hours offset
     pub fn with_timezone(timezone_hours: i8, timezone_minutes: i8) -> Self {
-        Self {
-            cancel: CancellationToken::new(),
-            timezone_minutes: (timezone_hours as i16) * 60 + (timezone_minutes as i16),
-        }
+        Self::with_timezone_minutes((timezone_hours as i16) * 60 + (timezone_minutes as i16))
     }
 
     /// create a new scheduler with timezone offset in minutes
     pub fn with_timezone_minutes(timezone_minutes: i16) -> Self {
+        Self::builder().timezone_minutes(timezone_minutes).build()
+    }
+
+    /// create a new scheduler with the current offset of the local timezone of the machine
+    ///
+    /// the offset is read once and kept, see `Scheduler::with_timezone_name` of the `tz`
+    /// feature to follow daylight saving. Fails where the offset cannot be read soundly, e.g. on Unix once
+    /// the process runs several threads
+    pub fn with_local_offset() -> Result<Self, String> {
+        let offset = UtcOffset::current_local_offset()
+            .map_err(|e| format!("Failed to read the local timezone offset: {e}"))?;
+        Ok(Self::with_timezone_minutes(offset.whole_minutes()))
+    }
+
+    /// create a new scheduler in an IANA timezone like `Europe/London`, following its
+    /// daylight saving changes
+    #[cfg(feature = "tz")]
+    pub fn with_timezone_name(name: &str) -> Result<Self, String> {
+        Ok(Self::builder().zone(crate::zone::parse(name)?).build())
+    }
+
+    /// create a builder to configure a new scheduler
+    pub fn builder() -> SchedulerBuilder {
+        SchedulerBuilder::default()
+    }
+
+    pub(crate) fn from_shared(shared: Shared) -> Self {
         Self {
             cancel: CancellationToken::new(),
-            timezone_minutes,
+            shared: Arc::new(shared),
+            next_id: AtomicU64::new(0),
+            tasks: Arc::default(),
+            joins: Mutex::new(Vec::new()),
+            loops: Arc::new(Mutex::new(HashMap::new())),
+            active: Arc::new(AtomicUsize::new(0)),
         }
     }
 
     /// run the task
-    pub async fn run<T: Notifiable + 'static>(&self, task: T) {
-        let schedule = task.get_task();
-        let cancel = self.cancel.clone();
-        let timezone_minutes = self.timezone_minutes;
+    ///
+    /// returns a handle to inspect the running task
+    pub async fn run<T: Notifiable + 'static>(&self, task: T) -> TaskHandle {
+        self.spawn(task, None, Duration::ZERO, None).await
+    }
+
+    /// run a boxed task, e.g. one of a list of tasks of different types
+    pub async fn run_dyn(&self, task: Box<dyn Notifiable>) -> TaskHandle {
+        self.run(task).await
+    }
+
+    /// run all tasks, returns their handles in the order of `tasks`
+    pub async fn run_all<T: Notifiable + 'static>(&self, tasks: Vec<T>) -> Vec<TaskHandle> {
+        let mut handles = Vec::with_capacity(tasks.len());
+        for task in tasks {
+            handles.push(self.run(task).await);
+        }
+        handles
+    }
+
+    /// run all tasks of mixed types, returns their handles in the order of `tasks`
+    pub async fn run_all_boxed(&self, tasks: Vec<Box<dyn Notifiable>>) -> Vec<TaskHandle> {
+        self.run_all(tasks).await
+    }
+
+    /// load tasks from a JSON config file and run them
+    ///
+    /// the file lists a schedule string, as accepted by [`Task::parse`], and a handler key
+    /// per task, e.g. `{"tasks": [{"schedule": "interval(60)", "handler": "report"}]}`.
+    /// `factory` turns a key and its parsed schedule into a task, returning `None` for an
+    /// unknown key. Nothing runs unless every entry is valid
+    #[cfg(feature = "serde")]
+    pub async fn load_config<F>(
+        &self,
+        path: impl AsRef<std::path::Path>,
+        factory: F,
+    ) -> Result<Vec<TaskHandle>, String>
+    where
+        F: FnMut(&str, Task) -> Option<Box<dyn Notifiable>>,
+    {
+        let path = path.as_ref();
+        let config = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read config '{}': {e}", path.display()))?;
+        self.load_config_str(&config, factory).await
+    }
+
+    /// like [`Scheduler::load_config`], reading the config from a string
+    #[cfg(feature = "serde")]
+    pub async fn load_config_str<F>(
+        &self,
+        config: &str,
+        factory: F,
+    ) -> Result<Vec<TaskHandle>, String>
+    where
+        F: FnMut(&str, Task) -> Option<Box<dyn Notifiable>>,
+    {
+        let tasks = config::build(config, self.shared.timezone_minutes, factory)?;
+        Ok(self.run_all_boxed(tasks).await)
+    }
 
-        match schedule {
-            Task::Wait(..) => {
-                Scheduler::run_wait(task, cancel.clone(), timezone_minutes).await;
+    /// run a clone of the task, so the caller keeps the task and its shared state
+    pub async fn run_ref<T: Notifiable + Clone + 'static>(&self, task: &T) -> TaskHandle {
+        self.run(task.clone()).await
+    }
+
+    /// run the task, delaying each trigger by a random offset in `0..=max_jitter`
+    ///
+    /// e.g. to spread the load of many instances started at the same time. The offsets
+    /// do not add up over the triggers of an interval task. `FixedRate` tasks keep
+    /// their grid and are not delayed
+    pub async fn run_with_jitter<T: Notifiable + 'static>(
+        &self,
+        task: T,
+        max_jitter: Duration,
+    ) -> TaskHandle {
+        self.spawn(task, None, max_jitter, None).await
+    }
+
+    /// run the task once `delay` has passed, e.g. to start an interval in 30 seconds
+    ///
+    /// the schedule starts after the delay, so an `Interval(10)` first fires `delay` plus
+    /// 10 seconds from now. Cancelling the task during the delay means it never starts
+    pub async fn run_after<T: Notifiable + 'static>(&self, delay: Duration, task: T) -> TaskHandle {
+        let at = time::Duration::try_from(delay)
+            .ok()
+            .and_then(|delay| self.shared.now().checked_add(delay));
+        let ready = Box::pin(async move {
+            tokio::time::sleep_until(after(Instant::now(), delay)).await;
+            true
+        });
+        let start_when = StartWhen { ready, at };
+        self.spawn(task, None, Duration::ZERO, Some(start_when))
+            .await
+    }
+
+    /// run the task `delay` after the handler of the task of `handle` returns, e.g. a
+    /// cleanup 10 minutes after an import
+    ///
+    /// each further fire of that task starts the delay again, so the task starts `delay`
+    /// after its last fire. It never starts if that task stops without having fired
+    pub async fn run_after_fire<T: Notifiable + 'static>(
+        &self,
+        handle: &TaskHandle,
+        delay: Duration,
+        task: T,
+    ) -> TaskHandle {
+        let mut events = self.shared.events.subscribe();
+        let id = handle.id();
+        // ids are only unique within a scheduler, a handle of another one never matches
+        let running = self
+            .tasks
+            .lock()
+            .unwrap()
+            .get(&id)
+            .is_some_and(|registered| Arc::ptr_eq(registered, &handle.state))
+            && self.loops.lock().unwrap().contains_key(&id);
+        let ready = Box::pin(async move {
+            if !running {
+                return false;
+            }
+            let mut deadline: Option<Instant> = None;
+            loop {
+                let event = select! {
+                    _ = sleep_until(deadline.unwrap_or_else(Instant::now)), if deadline.is_some() => {
+                        return true;
+                    }
+                    event = events.recv() => event,
+                };
+                match event {
+                    Ok(TaskEvent::Fired { id: fired, .. }) if fired == id => {
+                        deadline = Some(after(Instant::now(), delay));
+                    }
+                    Ok(TaskEvent::Stopped { id: stopped }) if stopped == id => break,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                    _ => {}
+                }
+            }
+            match deadline {
+                Some(deadline) => {
+                    sleep_until(deadline).await;
+                    true
+                }
+                None => false,
+            }
+        });
+        let start_when = StartWhen { ready, at: None };
+        self.spawn(task, None, Duration::ZERO, Some(start_when))
+            .await
+    }
+
+    /// run the task until its handler has been called `fires` times, then cancel it
+    ///
+    /// skipped triggers are not counted, nor failed calls if the task
+    /// [counts successes only](Notifiable::count_successes_only)
+    pub async fn run_for_fires<T: Notifiable + 'static>(&self, task: T, fires: u64) -> TaskHandle {
+        self.spawn(task, Some(fires), Duration::ZERO, None).await
+    }
+
+    /// run the task, sending the value produced by each trigger over `tx`
+    ///
+    /// the task is cancelled once the receiver is dropped
+    pub async fn run_collecting<T: Producer + 'static>(
+        &self,
+        task: T,
+        tx: mpsc::Sender<T::Output>,
+    ) -> TaskHandle {
+        self.spawn(Collecting { task, tx }, None, Duration::ZERO, None)
+            .await
+    }
+
+    /// replace the running tasks with `tasks`
+    ///
+    /// running tasks with an equal [`Task`] are kept untouched, the others are cancelled,
+    /// and tasks without a running match are started. Returns the handles in the order
+    /// of `tasks`
+    pub async fn reconcile<T: Notifiable + 'static>(&self, tasks: Vec<T>) -> Vec<TaskHandle> {
+        let mut matched = Vec::with_capacity(tasks.len());
+        {
+            let registry = self.tasks.lock().unwrap();
+            let mut running = registry
+                .values()
+                .filter(|state| !state.cancel.is_cancelled())
+                .cloned()
+                .collect::<Vec<_>>();
+            for task in &tasks {
+                let schedule = task.get_task();
+                let position = running.iter().position(|state| state.task() == schedule);
+                matched.push(position.map(|position| running.remove(position)));
+            }
+            for state in running {
+                tracing::debug!(id = state.id, name = %state.name, "reconcile cancel");
+                state.cancel.cancel();
             }
-            Task::Interval(..) => {
-                Scheduler::run_interval(task, cancel.clone(), timezone_minutes).await;
+        }
+
+        let mut handles = Vec::with_capacity(tasks.len());
+        for (task, state) in tasks.into_iter().zip(matched) {
+            let handle = match state {
+                Some(state) => TaskHandle { state },
+                None => self.spawn(task, None, Duration::ZERO, None).await,
+            };
+            handles.push(handle);
+        }
+        handles
+    }
+
+    async fn spawn<T: Notifiable + 'static>(
+        &self,
+        task: T,
+        max_fires: Option<u64>,
+        max_jitter: Duration,
+        start_when: Option<StartWhen>,
+    ) -> TaskHandle {
+        let schedule = task.get_task();
+        let now = self.shared.now();
+        let state = Arc::new(TaskState {
+            id: self.next_id.fetch_add(1, Ordering::Relaxed),
+            name: task.name(),
+            next_run: Mutex::new(None),
+            task: Mutex::new(Arc::new(schedule)),
+            firing: tokio::sync::Mutex::new(()),
+            created_at: now,
+            cancel: self.cancel.child_token(),
+            fires: AtomicU64::new(0),
+            max_fires,
+            warmup: AtomicU64::new(task.skip_first()),
+            at_behavior: task.at_behavior(),
+            max_jitter,
+            metrics: Mutex::default(),
+        });
+        if max_fires == Some(0) {
+            state.cancel.cancel();
+        }
+        {
+            let mut tasks = self.tasks.lock().unwrap();
+            tasks.retain(|_, state| !state.cancel.is_cancelled());
+            tasks.insert(state.id, state.clone());
+        }
+
+        self.shared
+            .emit(TaskEvent::Started {
+                id: state.id,
+                name: state.name.clone(),
+            })
+            .await;
+        let start: Arc<dyn Fn(Arc<TaskState>) -> Run + Send + Sync> = {
+            let (task, shared) = (Arc::new(task), self.shared.clone());
+            Arc::new(move |state| task_loop(task.clone(), state, shared.clone()))
+        };
+        let run = match start_when {
+            Some(StartWhen { ready, at }) => {
+                // the loop is only built once it starts, so the first run it reports
+                // follows the start
+                let schedule = state.task();
+                state.set_next_run(at.and_then(|at| self.first_run(&schedule, at)));
+                let (start, state, cancel) = (start.clone(), state.clone(), state.cancel.clone());
+                Box::pin(async move {
+                    select! {
+                        _ = cancel.cancelled() => {}
+                        go = ready => {
+                            if go {
+                                start(state).await;
+                            } else {
+                                cancel.cancel();
+                            }
+                        }
+                    }
+                }) as Run
             }
-            Task::At(..) => {
-                Scheduler::run_at(task, cancel.clone(), timezone_minutes).await;
+            None => start(state.clone()),
+        };
+        let stop = self.start_loop(&state, run);
+        self.loops
+            .lock()
+            .unwrap()
+            .insert(state.id, Loop { start, stop });
+
+        TaskHandle { state }
+    }
+
+    /// the first run of `schedule` after `from` that is not skipped, in wall clock time
+    fn first_run(&self, schedule: &Task, from: OffsetDateTime) -> Option<OffsetDateTime> {
+        schedule
+            .run_times(from)
+            .take(MAX_SKIPPED_RUNS)
+            .find(|time| !schedule.is_skipped(self.shared.local(*time)))
+            .map(|time| self.shared.wall_clock(schedule, time))
+    }
+
+    /// spawn the loop of a task, returns the token stopping it
+    fn start_loop(&self, state: &Arc<TaskState>, run: Run) -> CancellationToken {
+        let stop = CancellationToken::new();
+        #[cfg(feature = "prometheus")]
+        let active = self.shared.metrics.active(&state.name);
+        let count = ActiveCount::new(&self.active);
+        let (stopped, loops, id) = (stop.clone(), self.loops.clone(), state.id);
+        let tasks = self.tasks.clone();
+        let shared = self.shared.clone();
+        let join = tokio::spawn(async move {
+            #[cfg(feature = "prometheus")]
+            let _active = active;
+            let _count = count;
+            select! {
+                _ = run => {
+                    // done or cancelled, drop the task
+                    loops.lock().unwrap().remove(&id);
+                    tasks.lock().unwrap().remove(&id);
+                    shared.emit(TaskEvent::Stopped { id }).await;
+                }
+                _ = stopped.cancelled() => {}
             }
-            Task::Once(..) => {
-                Scheduler::run_once(task, cancel.clone(), timezone_minutes).await;
+        });
+        {
+            let mut joins = self.joins.lock().unwrap();
+            joins.retain(|(_, join)| !join.is_finished());
+            joins.push((state.clone(), join));
+        }
+        stop
+    }
+
+    /// replace the schedule of a task started by this scheduler, keeping its handle, id
+    /// and counters
+    ///
+    /// the loop on the old schedule stops once a running handler has returned, before
+    /// the loop on the new one starts, so the task never runs on both. Not to be called
+    /// from the handler of the task itself, it would wait for itself. An unchanged schedule
+    /// keeps the running loop, e.g. an interval keeps its phase
+    pub async fn reschedule(&self, handle: &TaskHandle, task: Task) -> Result<(), String> {
+        let state = &handle.state;
+        let _firing = state.firing.lock().await;
+        let registered = self
+            .tasks
+            .lock()
+            .unwrap()
+            .get(&state.id)
+            .is_some_and(|registered| Arc::ptr_eq(registered, state));
+        let running = self
+            .loops
+            .lock()
+            .unwrap()
+            .get(&state.id)
+            .map(|running| (running.start.clone(), running.stop.clone()));
+        let (start, stop) = match running {
+            Some(running) if registered && !state.cancel.is_cancelled() => running,
+            _ => {
+                return Err(format!(
+                    "Task {} is not running on this scheduler",
+                    state.id
+                ));
             }
+        };
+        if state.task() == task {
+            // keep the loop and its phase
+            return Ok(());
         }
+
+        stop.cancel();
+        let old = self
+            .joins
+            .lock()
+            .unwrap()
+            .extract_if(.., |(running, _)| Arc::ptr_eq(running, state))
+            .collect::<Vec<_>>();
+        for (_, join) in old {
+            // stopped at its next await point, outside of the handler
+            let _ = join.await;
+        }
+
+        tracing::debug!(id = state.id, name = %state.name, %task, "reschedule");
+        *state.task.lock().unwrap() = Arc::new(task);
+        let stop = self.start_loop(state, start(state.clone()));
+        self.loops
+            .lock()
+            .unwrap()
+            .insert(state.id, Loop { start, stop });
+        Ok(())
+    }
+
+    /// the number of task loops still running
+    ///
+    /// a task stops running once it is cancelled, or once it has no trigger left,
+    /// e.g. a `Wait` task after its trigger
+    pub fn active_task_count(&self) -> usize {
+        self.active.load(Ordering::Relaxed)
     }
 
+    /// the next run of `task` that is not skipped, in the scheduler timezone
+    ///
+    /// with a [zone](crate::SchedulerBuilder::zone), times of the day take the offset the
+    /// zone has on their date, like [`Scheduler::next_run_times`]
     pub fn get_next_run_time<T: Notifiable + 'static>(&self, task: T) -> Option<OffsetDateTime> {
         let schedule = task.get_task();
-        schedule.get_next_run_time::<T>(self.timezone_minutes)
+        schedule
+            .next_run_time_from(self.shared.now(), |time| self.shared.local(time))
+            .map(|time| self.shared.wall_clock(&schedule, time))
+    }
+
+    /// the next `count` runs of `task` that are not skipped, earliest first
+    ///
+    /// fewer are returned once the task is done, e.g. at most one for `Once`
+    pub fn next_run_times<T: Notifiable>(&self, task: T, count: usize) -> Vec<OffsetDateTime> {
+        let schedule = task.get_task();
+        let now = self.shared.now();
+        schedule
+            .run_times(now)
+            .take(count.saturating_add(MAX_SKIPPED_RUNS))
+            .filter(|time| !schedule.is_skipped(self.shared.local(*time)))
+            .take(count)
+            .map(|time| self.shared.wall_clock(&schedule, time))
+            .collect()
+    }
+
+    /// every trigger of `task` from now until `until`, with whether it would fire or which
+    /// skip rule of the task would skip it, without running anything
+    ///
+    /// the gate, maintenance windows and chaos skips of the scheduler are left out. At
+    /// most 10000 triggers are returned, and a schedule that stops advancing, like
+    /// `Interval(0)`, ends after its first trigger
+    pub fn simulate<T: Notifiable>(
+        &self,
+        task: T,
+        until: OffsetDateTime,
+    ) -> Vec<(OffsetDateTime, FireOutcome)> {
+        let schedule = task.get_task();
+        let now = self.shared.now();
+        let mut last: Option<OffsetDateTime> = None;
+        schedule
+            .run_times(now)
+            .take_while(|time| {
+                let advanced = last.is_none_or(|last| *time > last);
+                last = Some(*time);
+                advanced
+            })
+            .take(MAX_SIMULATED_RUNS)
+            .map(|time| (self.shared.wall_clock(&schedule, time), time))
+            .take_while(|(at, _)| *at <= until)
+            .map(|(at, time)| {
+                let outcome = match schedule.explain_skip(self.shared.local(time)) {
+                    Some(skip) => FireOutcome::Skip(skip.clone()),
+                    None => FireOutcome::Fire,
+                };
+                (at, outcome)
+            })
+            .collect()
+    }
+
+    /// export the upcoming runs of all tasks within `window` as an iCalendar (ICS) document
+    ///
+    /// repeating tasks without skip rules are exported as a single event with an `RRULE`,
+    /// everything else is enumerated one event per run
+    pub fn to_ics(&self, window: Duration) -> String {
+        let now = self.shared.now();
+        let until = now + window;
+        let tasks = self.tasks.lock().unwrap();
+        let tasks = tasks
+            .values()
+            .filter(|state| !state.cancel.is_cancelled())
+            .map(|state| {
+                let first = *state.next_run.lock().unwrap();
+                (state.as_ref(), first)
+            })
+            .collect::<Vec<_>>();
+        ics::render(&tasks, now, until, |time| self.shared.local(time))
+    }
+
+    /// the instants where more than `threshold` tasks fire within `window`, over the next day
+    ///
+    /// each cluster is reported once with the number of tasks in it, at its first run,
+    /// and logged as a warning
+    pub fn detect_clustering(
+        &self,
+        window: Duration,
+        threshold: usize,
+    ) -> Vec<(OffsetDateTime, usize)> {
+        let now = self.shared.now();
+        let until = now + CLUSTER_HORIZON;
+        let mut runs = Vec::new();
+        {
+            let tasks = self.tasks.lock().unwrap();
+            for state in tasks.values().filter(|state| !state.cancel.is_cancelled()) {
+                let first = *state.next_run.lock().unwrap();
+                let task = state.task();
+                let times = RunTimes::new(&task, first)
+                    .take_while(|time| *time <= until)
+                    .filter(|time| !task.is_skipped(self.shared.local(*time)))
+                    .take(MAX_CLUSTER_RUNS);
+                runs.extend(times.map(|time| (time, state.id)));
+            }
+        }
+        runs.sort();
+
+        let mut clusters = Vec::new();
+        let mut start = 0;
+        while let Some((at, _)) = runs.get(start).copied() {
+            let end = runs[start..]
+                .iter()
+                .position(|(time, _)| *time > at + window)
+                .map_or(runs.len(), |len| start + len);
+            let mut ids = runs[start..end]
+                .iter()
+                .map(|(_, id)| *id)
+                .collect::<Vec<_>>();
+            ids.sort_unstable();
+            ids.dedup();
+            if ids.len() > threshold {
+                tracing::warn!(%at, tasks = ids.len(), ?window, "tasks clustered");
+                clusters.push((at, ids.len()));
+                start = end;
+            } else {
+                start += 1;
+            }
+        }
+        clusters
+    }
+
+    /// a JSON dump of the scheduler and its tasks for debugging, e.g. behind a
+    /// `/debug/scheduler` endpoint
+    ///
+    /// times are RFC 3339 in the scheduler timezone, `null` when there is none. The
+    /// format is meant for people and may change
+    #[cfg(feature = "serde")]
+    pub fn debug_json(&self) -> String {
+        let format = |time: Option<OffsetDateTime>| {
+            time.and_then(|time| self.shared.local(time).format(&Rfc3339).ok())
+        };
+        let tasks = self
+            .tasks
+            .lock()
+            .unwrap()
+            .values()
+            .map(|state| {
+                let next_run = *state.next_run.lock().unwrap();
+                let metrics = state.metrics.lock().unwrap();
+                serde_json::json!({
+                    "id": state.id,
+                    "name": state.name,
+                    "schedule": state.task().to_string(),
+                    "cancelled": state.cancel.is_cancelled(),
+                    "next_run": format(next_run),
+                    "last_run": format(metrics.last_run),
+                    "executions": state.fires.load(Ordering::Relaxed),
+                    "skips": metrics.skips,
+                })
+            })
+            .collect::<Vec<_>>();
+        serde_json::json!({
+            "timezone_minutes": self.shared.timezone_minutes,
+            "cancelled": self.cancel.is_cancelled(),
+            "active_tasks": self.active_task_count(),
+            "tasks": tasks,
+        })
+        .to_string()
+    }
+
+    /// the most recent triggers of all tasks, oldest first
+    ///
+    /// empty unless enabled with [`SchedulerBuilder::history`]
+    pub fn recent_history(&self) -> Vec<HistoryEntry> {
+        self.shared
+            .history
+            .as_ref()
+            .map(History::entries)
+            .unwrap_or_default()
+    }
+
+    /// the prometheus registry with the metrics of the scheduler
+    ///
+    /// `easy_schedule_executions_total`, `easy_schedule_skips_total` and
+    /// `easy_schedule_active_tasks`, all labeled by task name
+    #[cfg(feature = "prometheus")]
+    pub fn metrics_registry(&self) -> &prometheus::Registry {
+        self.shared.metrics.registry()
+    }
+
+    /// wait for the next fire of any task, resolving with the task name
+    ///
+    /// resolves when the handler of the task returns, failed or not. Resolves with
+    /// `None` right away if no task is running. Safe to use in `select!`
+    pub async fn next_fire(&self) -> Option<String> {
+        // subscribe before checking, so a task can not fire in between
+        let mut fires = self.shared.fires.subscribe();
+        let running = self
+            .joins
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|(_, join)| !join.is_finished());
+        if !running {
+            return None;
+        }
+        loop {
+            match fires.recv().await {
+                Ok(name) => return Some(name),
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    }
+
+    /// subscribe to the lifecycle events of all tasks, e.g. for a dashboard
+    ///
+    /// only events sent after subscribing are received, a receiver falling more than 256
+    /// events behind misses the oldest ones
+    pub fn subscribe(&self) -> broadcast::Receiver<TaskEvent> {
+        self.shared.events.subscribe()
+    }
+
+    /// call `on_missed` whenever the task of `handle` goes `expected_interval` without
+    /// firing, e.g. to alert on a daily job that did not run
+    ///
+    /// the window starts now, and again on each fire and each call of `on_missed`. The
+    /// watchdog stops with the task
+    pub fn watchdog<F>(&self, handle: &TaskHandle, expected_interval: Duration, mut on_missed: F)
+    where
+        F: FnMut(&TaskHandle) + Send + 'static,
+    {
+        let mut events = self.shared.events.subscribe();
+        let handle = handle.clone();
+        tokio::spawn(async move {
+            let (id, cancel) = (handle.id(), handle.state.cancel.clone());
+            let mut deadline = after(Instant::now(), expected_interval);
+            loop {
+                select! {
+                    _ = cancel.cancelled() => return,
+                    _ = sleep_until(deadline) => {
+                        tracing::warn!(id, name = %handle.name(), "task missed its window");
+                        on_missed(&handle);
+                        deadline = after(Instant::now(), expected_interval);
+                    }
+                    event = events.recv() => match event {
+                        Ok(TaskEvent::Fired { id: fired, .. }) if fired == id => {
+                            deadline = after(Instant::now(), expected_interval);
+                        }
+                        Ok(TaskEvent::Stopped { id: stopped }) if stopped == id => return,
+                        Err(broadcast::error::RecvError::Closed) => return,
+                        _ => {}
+                    }
+                }
+            }
+        });
+    }
+
+    /// a channel receiving the lifecycle events of all tasks, none of them lost
+    ///
+    /// sending waits for room in the channel, so a consumer slower than the tasks slows
+    /// the scheduler down: task loops wait before they fire, skip or stop. The channel
+    /// is dropped once the receiver is. See [`Scheduler::try_lifecycle_channel`] to drop
+    /// events instead
+    pub fn lifecycle_channel(&self, capacity: usize) -> mpsc::Receiver<TaskEvent> {
+        self.lifecycle_with(capacity, true)
+    }
+
+    /// like [`Scheduler::lifecycle_channel`], dropping the events that find the channel
+    /// full instead of waiting
+    pub fn try_lifecycle_channel(&self, capacity: usize) -> mpsc::Receiver<TaskEvent> {
+        self.lifecycle_with(capacity, false)
+    }
+
+    fn lifecycle_with(&self, capacity: usize, wait: bool) -> mpsc::Receiver<TaskEvent> {
+        let (tx, rx) = mpsc::channel(capacity.max(1));
+        self.shared.lifecycle.lock().unwrap().push((tx, wait));
+        rx
     }
 
     /// stop the scheduler
@@ -72,153 +992,643 @@ impl Scheduler {
         self.cancel.cancel();
     }
 
+    /// stop the scheduler and wait for the running handlers to finish
+    ///
+    /// see [`Scheduler::shutdown_timeout`] to bound the wait
+    pub async fn shutdown(self) {
+        self.stop();
+        let joins = std::mem::take(&mut *self.joins.lock().unwrap());
+        for (state, join) in joins {
+            if let Err(err) = join.await {
+                tracing::warn!(id = state.id, name = %state.name, error = %err, "task failed on shutdown");
+            }
+        }
+    }
+
+    /// stop the scheduler and wait up to `timeout` for the running tasks to finish
+    ///
+    /// tasks still running after the timeout, e.g. stuck in `on_time`, are aborted
+    /// and returned
+    pub async fn shutdown_timeout(&self, timeout: Duration) -> Vec<TaskHandle> {
+        self.stop();
+        let joins = std::mem::take(&mut *self.joins.lock().unwrap());
+        let deadline = after(Instant::now(), timeout);
+        let mut aborted = Vec::new();
+        for (state, mut join) in joins {
+            if tokio::time::timeout_at(deadline, &mut join).await.is_err() {
+                join.abort();
+                tracing::warn!(id = state.id, name = %state.name, "task aborted on shutdown");
+                aborted.push(TaskHandle { state });
+            }
+        }
+        aborted
+    }
+
     /// get the cancel token
     pub fn get_cancel(&self) -> CancellationToken {
         self.cancel.clone()
     }
+
+    /// skip the triggers of all tasks for `duration` from each time matching `start_cron`,
+    /// like a closed [`SchedulerBuilder::gate`]
+    ///
+    /// `start_cron` is a cron expression in the scheduler timezone, see [`Task::Cron`].
+    /// Several windows can be added
+    pub fn add_maintenance_window(
+        &self,
+        start_cron: &str,
+        duration: Duration,
+    ) -> Result<(), String> {
+        let cron = CronExpr::parse(start_cron)?;
+        let length = time::Duration::try_from(duration)
+            .map_err(|_| format!("Invalid maintenance window duration {duration:?}"))?;
+        self.shared.maintenance.lock().unwrap().push((cron, length));
+        Ok(())
+    }
+
+    /// the timezone offset of the scheduler in minutes, as configured
+    pub fn offset_minutes(&self) -> i16 {
+        self.shared.timezone_minutes
+    }
+
+    /// the timezone offset of the scheduler, UTC if the configured minutes are out of
+    /// range, like the task loops fall back to
+    pub fn offset(&self) -> UtcOffset {
+        UtcOffset::from_whole_seconds(self.shared.timezone_minutes as i32 * 60)
+            .unwrap_or(UtcOffset::UTC)
+    }
 }
 
 impl Scheduler {
     /// run wait task
-    #[instrument(skip(cancel))]
+    #[instrument(skip(state, shared))]
     async fn run_wait<T: Notifiable + 'static>(
         task: T,
-        cancel: CancellationToken,
-        timezone_minutes: i16,
+        state: Arc<TaskState>,
+        shared: Arc<Shared>,
     ) {
-        if let Task::Wait(wait, skip) = task.get_task() {
-            let task_ref = task;
-            tokio::task::spawn(async move {
-                select! {
-                    _ = cancel.cancelled() => {
-                        return;
-                    }
-                    _ = sleep(Duration::from_secs(wait)) => {
-                        tracing::debug!(wait, "wait seconds");
-                    }
-                };
-                let now = get_now(timezone_minutes).unwrap_or_else(|_| OffsetDateTime::now_utc());
-                if let Some(skip) = skip {
-                    if skip.iter().any(|s| s.is_skip(now)) {
-                        task_ref.on_skip(cancel.clone()).await;
-                        return;
-                    }
-                }
-                task_ref.on_time(cancel.clone()).await;
-            });
+        let (wait, skip) = match state.task().without_window() {
+            Task::Wait(wait, skip) => (Duration::from_secs(wait), skip),
+            Task::WaitMillis(wait, skip) => (Duration::from_millis(wait), skip),
+            _ => return,
+        };
+        let task_ref = task;
+        let cancel = state.cancel.clone();
+        let deadline = after(
+            Instant::now(),
+            wait.saturating_add(shared.jitter(state.max_jitter)),
+        );
+        select! {
+            _ = cancel.cancelled() => {
+                return;
+            }
+            _ = sleep_until(deadline) => {
+                tracing::debug!(?wait, "wait");
+            }
+        };
+        state.set_next_run(None);
+        let now = shared.now();
+        let reason = Scheduler::wake_reason(&task_ref, &state, deadline)
+            .or_else(|| shared.skip_reason(skip.as_deref(), now))
+            .or_else(|| state.take_warmup());
+        if let Some(reason) = reason {
+            Scheduler::skip(&task_ref, &state, &shared, reason).await;
+            return;
         }
+        Scheduler::fire(&task_ref, &state, &shared).await;
     }
 
     /// run interval task
-    #[instrument(skip(cancel))]
+    #[instrument(skip(state, shared))]
     async fn run_interval<T: Notifiable + 'static>(
         task: T,
-        cancel: CancellationToken,
-        timezone_minutes: i16,
+        state: Arc<TaskState>,
+        shared: Arc<Shared>,
     ) {
-        if let Task::Interval(interval, skip) = task.get_task() {
-            let task_ref = task;
-            tokio::task::spawn(async move {
-                loop {
-                    select! {
-                        _ = cancel.cancelled() => {
-                            return;
-                        }
-                        _ = sleep(Duration::from_secs(interval)) => {
-                            tracing::debug!(interval, "interval");
-                        }
-                    };
-                    let now =
-                        get_now(timezone_minutes).unwrap_or_else(|_| OffsetDateTime::now_utc());
-                    if let Some(ref skip) = skip {
-                        if skip.iter().any(|s| s.is_skip(now)) {
-                            task_ref.on_skip(cancel.clone()).await;
-                            continue;
+        let (interval, skip) = match state.task().without_window() {
+            Task::Interval(interval, skip) => (Duration::from_secs(interval), skip),
+            Task::IntervalMillis(interval, skip) => (Duration::from_millis(interval), skip),
+            _ => return,
+        };
+        let task_ref = task;
+        let cancel = state.cancel.clone();
+        let breaker = task_ref.circuit_breaker();
+        let bounds = task_ref.delay_bounds();
+        let retry = task_ref.retry_until_first_success();
+        let mut succeeded = retry.is_none();
+        let mut failures = 0;
+        let mut open = false;
+        // delay hint of the last trigger
+        let mut hint: Option<Duration> = None;
+        let mut last_jitter = Duration::ZERO;
+        let align = task_ref.align_interval() && divides_hour(interval);
+        // boundary of the last aligned trigger, so a boundary is never handled twice
+        let mut last_boundary: Option<OffsetDateTime> = None;
+        loop {
+            let now = shared.now();
+            let wait = match breaker {
+                Some(breaker) if open => breaker.cooldown,
+                _ if !succeeded => retry.unwrap_or(interval),
+                _ => match hint.take() {
+                    Some(hint) => hint,
+                    None if align => {
+                        let mut boundary = next_boundary(now, interval);
+                        if last_boundary.is_some_and(|last| boundary <= last) {
+                            boundary += interval;
                         }
+                        last_boundary = Some(boundary);
+                        Duration::try_from(boundary - now).unwrap_or_default()
                     }
-                    task_ref.on_time(cancel.clone()).await;
+                    None => interval,
+                },
+            };
+            let jitter = shared.jitter(state.max_jitter);
+            // the jitter of the last trigger is taken back, so jitter does not add up
+            let start = Instant::now();
+            let deadline = after(
+                start.checked_sub(last_jitter).unwrap_or(start),
+                wait.saturating_add(jitter),
+            );
+            last_jitter = jitter;
+            state.set_next_run(Some(now + deadline.saturating_duration_since(start)));
+            select! {
+                _ = cancel.cancelled() => {
+                    return;
                 }
-            });
+                _ = sleep_until(deadline) => {
+                    tracing::debug!(?interval, "interval");
+                }
+            };
+            if open {
+                open = false;
+                tracing::info!("circuit closed");
+                task_ref.on_circuit_close(cancel.clone()).await;
+            }
+            let now = shared.now();
+            let reason = Scheduler::wake_reason(&task_ref, &state, deadline)
+                .or_else(|| shared.skip_reason(skip.as_deref(), now))
+                .or_else(|| state.take_warmup());
+            if let Some(reason) = reason {
+                Scheduler::skip(&task_ref, &state, &shared, reason).await;
+                continue;
+            }
+            if let Ok(delay) = Scheduler::fire_adaptive(&task_ref, &state, &shared).await {
+                failures = 0;
+                succeeded = true;
+                hint = delay.map(|delay| delay.max(*bounds.start()).min(*bounds.end()));
+                continue;
+            }
+            failures += 1;
+            if let Some(breaker) = breaker
+                && failures >= breaker.threshold
+            {
+                // a single failure after the cooldown opens the circuit again
+                failures = breaker.threshold.saturating_sub(1);
+                open = true;
+                tracing::warn!(?breaker.cooldown, "circuit opened");
+                task_ref.on_circuit_open(cancel.clone()).await;
+            }
         }
     }
 
-    /// run at task
-    #[instrument(skip(cancel))]
-    async fn run_at<T: Notifiable + 'static>(
+    /// run fixed rate task
+    #[instrument(skip(state, shared))]
+    async fn run_fixed_rate<T: Notifiable + 'static>(
         task: T,
-        cancel: CancellationToken,
-        timezone_minutes: i16,
+        state: Arc<TaskState>,
+        shared: Arc<Shared>,
     ) {
-        if let Task::At(time, skip) = task.get_task() {
+        if let Task::FixedRate(interval, skip) = state.task().without_window() {
             let task_ref = task;
-            tokio::task::spawn(async move {
-                let now = get_now(timezone_minutes).unwrap_or_else(|_| OffsetDateTime::now_utc());
-                let mut next = get_next_time(now, time);
-                loop {
-                    let now =
-                        get_now(timezone_minutes).unwrap_or_else(|_| OffsetDateTime::now_utc());
-                    let seconds = (next - now).as_seconds_f64() as u64;
-                    let instant = Instant::now() + Duration::from_secs(seconds);
-                    select! {
-                        _ = cancel.cancelled() => {
-                            return;
-                        }
-                        _ = sleep_until(instant) => {
-                            tracing::debug!("at time");
-                        }
+            let cancel = state.cancel.clone();
+            // a zero period panics in tokio
+            let period = Duration::from_secs(interval.max(1));
+            let anchor = Instant::now();
+            let mut ticks = tokio::time::interval_at(anchor + period, period);
+            // ticks missed by a slow handler are dropped, the next one stays on the grid
+            ticks.set_missed_tick_behavior(MissedTickBehavior::Skip);
+            loop {
+                let elapsed = Instant::now().saturating_duration_since(anchor);
+                let steps = elapsed.as_nanos() / period.as_nanos() + 1;
+                let next = anchor + period * steps as u32;
+                let now = shared.now();
+                state.set_next_run(Some(now + next.saturating_duration_since(Instant::now())));
+                let deadline = select! {
+                    _ = cancel.cancelled() => {
+                        return;
                     }
-
-                    if let Some(skip) = skip.clone() {
-                        if skip.iter().any(|s| s.is_skip(next)) {
-                            task_ref.on_skip(cancel.clone()).await;
-                            next += time::Duration::days(1);
-                            continue;
-                        }
+                    deadline = ticks.tick() => {
+                        tracing::debug!(interval, "fixed rate");
+                        deadline
                     }
+                };
+                let now = shared.now();
+                let reason = Scheduler::wake_reason(&task_ref, &state, deadline)
+                    .or_else(|| shared.skip_reason(skip.as_deref(), now))
+                    .or_else(|| state.take_warmup());
+                if let Some(reason) = reason {
+                    Scheduler::skip(&task_ref, &state, &shared, reason).await;
+                    continue;
+                }
+                Scheduler::fire(&task_ref, &state, &shared).await;
+                if task_ref.skip_if_running() {
+                    // ticks strictly between the fired one and now
+                    let late = Instant::now().saturating_duration_since(deadline);
+                    let missed = late
+                        .as_nanos()
+                        .div_ceil(period.as_nanos())
+                        .saturating_sub(1);
+                    for _ in 0..missed {
+                        Scheduler::skip(&task_ref, &state, &shared, SkipReason::Overlap).await;
+                    }
+                }
+            }
+        }
+    }
 
-                    task_ref.on_time(cancel.clone()).await;
-
+    /// run at task
+    #[instrument(skip(state, shared))]
+    async fn run_at<T: Notifiable + 'static>(task: T, state: Arc<TaskState>, shared: Arc<Shared>) {
+        let schedule = state.task().without_window();
+        if let Task::At(time, skip) = schedule.clone() {
+            let task_ref = task;
+            let cancel = state.cancel.clone();
+            // date of the last trigger, so a day is never handled twice
+            let mut last_date: Option<Date> = None;
+            let mut catch_up = state.at_behavior == AtBehavior::CatchUp;
+            if !catch_up {
+                let now = shared.now();
+                let today = now.replace_time(time);
+                if today < now.replace_nanosecond(0).unwrap_or(now)
+                    && !shared.rule_skip(skip.as_deref(), today)
+                {
+                    // the time of today already passed when started
+                    Scheduler::skip(&task_ref, &state, &shared, SkipReason::Missed).await;
+                }
+            }
+            loop {
+                let now = shared.now();
+                // an at time within the current second is due now, not tomorrow
+                let second = now.replace_nanosecond(0).unwrap_or(now);
+                let mut next = get_next_time(second, time);
+                if std::mem::take(&mut catch_up) {
+                    // the time of today already passed when started
+                    next = next.min(now.replace_time(time));
+                }
+                while last_date.is_some_and(|date| next.date() <= date) {
                     next += time::Duration::days(1);
                 }
-            });
+                let next = shared.wall_clock(&schedule, next);
+                state.set_next_run(Some(next));
+                // a next time already passed fires right away
+                let deadline = after(
+                    Instant::now(),
+                    Duration::try_from(next - now)
+                        .unwrap_or_default()
+                        .saturating_add(shared.jitter(state.max_jitter)),
+                );
+                select! {
+                    _ = cancel.cancelled() => {
+                        return;
+                    }
+                    _ = sleep_until(deadline) => {
+                        tracing::debug!("at time");
+                    }
+                }
+                last_date = Some(next.date());
+
+                let reason = Scheduler::wake_reason(&task_ref, &state, deadline)
+                    .or_else(|| shared.skip_reason(skip.as_deref(), next))
+                    .or_else(|| state.take_warmup());
+                if let Some(reason) = reason {
+                    Scheduler::skip(&task_ref, &state, &shared, reason).await;
+                    continue;
+                }
+
+                Scheduler::fire(&task_ref, &state, &shared).await;
+            }
         }
     }
 
     /// run once task
-    #[instrument(skip(task, cancel))]
+    #[instrument(skip(task, state, shared))]
     async fn run_once<T: Notifiable + 'static>(
         task: T,
-        cancel: CancellationToken,
-        timezone_minutes: i16,
+        state: Arc<TaskState>,
+        shared: Arc<Shared>,
     ) {
-        if let Task::Once(next, skip) = task.get_task() {
+        if let Task::Once(next, skip) = state.task().without_window() {
             let task_ref = task;
-            tokio::task::spawn(async move {
-                let now = get_now(timezone_minutes).unwrap_or_else(|_| OffsetDateTime::now_utc());
-                if next < now {
-                    task_ref.on_skip(cancel.clone()).await;
+            let cancel = state.cancel.clone();
+            let now = shared.now();
+            if next < now {
+                Scheduler::skip(&task_ref, &state, &shared, SkipReason::Missed).await;
+                return;
+            }
+
+            if shared.rule_skip(skip.as_deref(), next) {
+                Scheduler::skip(&task_ref, &state, &shared, SkipReason::Rule).await;
+                return;
+            }
+            let deadline = after(
+                Instant::now(),
+                Duration::try_from(next - now)
+                    .unwrap_or_default()
+                    .saturating_add(shared.jitter(state.max_jitter)),
+            );
+
+            select! {
+                _ = cancel.cancelled() => {
                     return;
                 }
+                _ = sleep_until(deadline) => {
+                    tracing::debug!("once time");
+                }
+            }
+            state.set_next_run(None);
+            let reason = Scheduler::wake_reason(&task_ref, &state, deadline)
+                .or_else(|| shared.scheduler_skip())
+                .or_else(|| state.take_warmup());
+            if let Some(reason) = reason {
+                Scheduler::skip(&task_ref, &state, &shared, reason).await;
+                return;
+            }
+            Scheduler::fire(&task_ref, &state, &shared).await;
+        }
+    }
 
-                if let Some(skip) = skip {
-                    if skip.iter().any(|s| s.is_skip(next)) {
-                        task_ref.on_skip(cancel.clone()).await;
-                        return;
-                    }
+    /// run cron task
+    #[instrument(skip(state, shared))]
+    async fn run_cron<T: Notifiable + 'static>(
+        task: T,
+        state: Arc<TaskState>,
+        shared: Arc<Shared>,
+    ) {
+        if let Task::Cron(expr, _) = state.task().without_window() {
+            if let Err(err) = CronExpr::parse(&expr) {
+                tracing::warn!(error = %err, "invalid cron expression");
+                state.set_next_run(None);
+                return;
+            }
+            Scheduler::run_calendar(task, state, shared).await;
+        }
+    }
+
+    /// run a task triggered at wall clock times computed from its schedule
+    #[instrument(skip(state, shared))]
+    async fn run_calendar<T: Notifiable + 'static>(
+        task: T,
+        state: Arc<TaskState>,
+        shared: Arc<Shared>,
+    ) {
+        let schedule = state.task().without_window();
+        let task_ref = task;
+        let cancel = state.cancel.clone();
+        // time of the last trigger, so a time is never handled twice
+        let mut last: Option<OffsetDateTime> = None;
+        loop {
+            let now = shared.now();
+            let from = last.map_or(now, |last| last.max(now));
+            let Some(next) = schedule
+                .run_times(from)
+                .find(|next| last.is_none_or(|last| *next > last))
+            else {
+                state.set_next_run(None);
+                return;
+            };
+            let at = shared.wall_clock(&schedule, next);
+            state.set_next_run(Some(at));
+            let deadline = after(
+                Instant::now(),
+                Duration::try_from(at - now)
+                    .unwrap_or_default()
+                    .saturating_add(shared.jitter(state.max_jitter)),
+            );
+            select! {
+                _ = cancel.cancelled() => {
+                    return;
+                }
+                _ = sleep_until(deadline) => {
+                    tracing::debug!(%at, "calendar time");
                 }
-                let seconds = (next - now).as_seconds_f64();
-                let instant = Instant::now() + Duration::from_secs(seconds as u64);
+            }
+            last = Some(next);
 
-                select! {
-                    _ = cancel.cancelled() => {
-                        return;
-                    }
-                    _ = sleep_until(instant) => {
-                        tracing::debug!("once time");
-                    }
+            let reason = Scheduler::wake_reason(&task_ref, &state, deadline)
+                .or_else(|| shared.skip_reason(Some(schedule.skips()), next))
+                .or_else(|| state.take_warmup());
+            if let Some(reason) = reason {
+                Scheduler::skip(&task_ref, &state, &shared, reason).await;
+                continue;
+            }
+            Scheduler::fire(&task_ref, &state, &shared).await;
+            if task_ref.skip_if_running() {
+                let now = shared.now();
+                let missed = schedule
+                    .run_times(next)
+                    .skip_while(|time| *time <= next)
+                    .take_while(|time| shared.wall_clock(&schedule, *time) < now)
+                    .collect::<Vec<_>>();
+                for time in missed {
+                    Scheduler::skip(&task_ref, &state, &shared, SkipReason::Overlap).await;
+                    last = Some(time);
                 }
-                task_ref.on_time(cancel.clone()).await;
-            });
+            }
+        }
+    }
+
+    /// run `run`, the loop of the task a [`Task::Within`] limits, from `start` until `end`
+    ///
+    /// the loop is dropped once `end` is reached and no handler runs, so a handler running
+    /// then is not cut short
+    async fn run_within(
+        run: Run,
+        state: Arc<TaskState>,
+        shared: Arc<Shared>,
+        start: OffsetDateTime,
+        end: OffsetDateTime,
+    ) {
+        let (now, instant) = (shared.now(), Instant::now());
+        let deadline =
+            |at: OffsetDateTime| after(instant, Duration::try_from(at - now).unwrap_or_default());
+        select! {
+            _ = state.cancel.cancelled() => {
+                return;
+            }
+            _ = sleep_until(deadline(start)) => {}
+        }
+        let end = async {
+            sleep_until(deadline(end)).await;
+            state.firing.lock().await
+        };
+        select! {
+            biased;
+            _firing = end => {
+                tracing::debug!("window ended");
+                state.set_next_run(None);
+            }
+            _ = run => {}
+        }
+    }
+
+    /// call the handler of a triggered task, returns `false` if it failed
+    async fn fire<T: Notifiable>(task: &T, state: &TaskState, shared: &Shared) -> bool {
+        Scheduler::fire_adaptive(task, state, shared).await.is_ok()
+    }
+
+    /// call the handler of a triggered task, returns its hint for the next delay
+    async fn fire_adaptive<T: Notifiable>(
+        task: &T,
+        state: &TaskState,
+        shared: &Shared,
+    ) -> Result<Option<Duration>, TaskError> {
+        let _firing = state.firing.lock().await;
+        let at = shared.now();
+        let flight = task.single_flight_key().map(|key| shared.flight(key));
+        let _flight = match &flight {
+            Some(flight) => Some(flight.lock().await),
+            None => None,
+        };
+        let fire = task.on_time_adaptive(state.cancel.clone());
+        #[cfg(feature = "otel")]
+        let fire = tracing::Instrument::instrument(fire, fire_span(state));
+        let (result, outcome) = match AssertUnwindSafe(fire).catch_unwind().await {
+            Ok(Ok(delay)) => (Ok(delay), Outcome::Fired),
+            Ok(Err(err)) => {
+                tracing::warn!(error = %err, "task failed");
+                task.on_error(&err, state.cancel.clone()).await;
+                (Err(err), Outcome::Failed)
+            }
+            Err(panic) => {
+                let message = panic_message(panic.as_ref());
+                tracing::error!(message, "task panicked");
+                if task.on_panic(&message, state.cancel.clone()).await == PanicAction::Stop {
+                    state.cancel.cancel();
+                }
+                (
+                    Err(format!("task panicked: {message}").into()),
+                    Outcome::Failed,
+                )
+            }
+        };
+        shared.record(state, outcome);
+        // no receiver is not an error
+        let _ = shared.fires.send(state.name.clone());
+        shared.emit(TaskEvent::Fired { id: state.id, at }).await;
+        if result.is_ok() || !task.count_successes_only() {
+            state.count_fire();
         }
+        result
+    }
+
+    /// call the skip handler of a triggered task
+    async fn skip<T: Notifiable>(task: &T, state: &TaskState, shared: &Shared, reason: SkipReason) {
+        tracing::debug!(?reason, "skip");
+        task.on_skip_with_reason(state.cancel.clone(), reason).await;
+        shared.record(state, Outcome::Skipped);
+        shared
+            .emit(TaskEvent::Skipped {
+                id: state.id,
+                reason,
+            })
+            .await;
+    }
+
+    /// record the drift of a trigger that woke up for `deadline`, returns `Stale` if it
+    /// woke up further past it than the task allows
+    fn wake_reason<T: Notifiable>(
+        task: &T,
+        state: &TaskState,
+        deadline: Instant,
+    ) -> Option<SkipReason> {
+        let late = Instant::now().saturating_duration_since(deadline);
+        state.record_drift(late);
+        task.staleness()
+            .is_some_and(|staleness| late > staleness)
+            .then_some(SkipReason::Stale)
     }
 }
+
+/// the loop running `task` on the current schedule of `state`
+fn task_loop<T: Notifiable + 'static>(
+    task: Arc<T>,
+    state: Arc<TaskState>,
+    shared: Arc<Shared>,
+) -> Run {
+    let schedule = state.task();
+    state.set_next_run(schedule.next_run_time_from(shared.now(), |time| shared.local(time)));
+    schedule_loop(&schedule, task, state, shared)
+}
+
+/// the loop of `schedule`, the loops read the schedule from `state` without its windows
+fn schedule_loop<T: Notifiable + 'static>(
+    schedule: &Task,
+    task: Arc<T>,
+    state: Arc<TaskState>,
+    shared: Arc<Shared>,
+) -> Run {
+    match schedule {
+        Task::Wait(..) | Task::WaitMillis(..) => Box::pin(Scheduler::run_wait(task, state, shared)),
+        Task::Interval(..) | Task::IntervalMillis(..) => {
+            Box::pin(Scheduler::run_interval(task, state, shared))
+        }
+        Task::FixedRate(..) => Box::pin(Scheduler::run_fixed_rate(task, state, shared)),
+        Task::At(..) => Box::pin(Scheduler::run_at(task, state, shared)),
+        Task::Once(..) => Box::pin(Scheduler::run_once(task, state, shared)),
+        Task::IntervalPhased(..)
+        | Task::AtUntil(..)
+        | Task::AtMulti(..)
+        | Task::Hourly(..)
+        | Task::Weekly(..)
+        | Task::BusinessDayInterval(..)
+        | Task::AtComputed(..)
+        | Task::OnceOn(..) => Box::pin(Scheduler::run_calendar(task, state, shared)),
+        Task::Cron(..) => Box::pin(Scheduler::run_cron(task, state, shared)),
+        Task::Within(inner, start, end) => {
+            let run = schedule_loop(inner, task, state.clone(), shared.clone());
+            Box::pin(Scheduler::run_within(run, state, shared, *start, *end))
+        }
+    }
+}
+
+/// a root span for a single fire, so every fire gets its own trace id once exported
+/// through `tracing-opentelemetry`
+#[cfg(feature = "otel")]
+fn fire_span(state: &TaskState) -> tracing::Span {
+    tracing::info_span!(
+        parent: None,
+        "fire",
+        otel.name = %state.name,
+        otel.kind = "internal",
+        task.id = state.id,
+        task.name = %state.name,
+    )
+}
+
+/// check if an hour is a whole number of `interval`, see [`Notifiable::align_interval`]
+fn divides_hour(interval: Duration) -> bool {
+    const HOUR: u128 = 3600 * 1_000_000_000;
+    let interval = interval.as_nanos();
+    interval > 0 && HOUR.is_multiple_of(interval)
+}
+
+/// the first multiple of `interval` after `now`, counted from the start of its hour
+fn next_boundary(now: OffsetDateTime, interval: Duration) -> OffsetDateTime {
+    let hour = now
+        .replace_minute(0)
+        .and_then(|hour| hour.replace_second(0))
+        .and_then(|hour| hour.replace_nanosecond(0))
+        .unwrap_or(now);
+    let step = interval.as_nanos() as i128;
+    let steps = (now - hour).whole_nanoseconds() / step + 1;
+    hour + time::Duration::nanoseconds((steps * step) as i64)
+}
+
+/// the message of a caught panic, for `panic!` with a literal or a format string
+fn panic_message(panic: &(dyn Any + Send)) -> String {
+    panic
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| panic.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
@@ -0,0 +1,101 @@
+use crate::task::{Skip, Task};
+
+/// parse a skip expression, see [`Skip::parse_expr`]
+///
+/// ```text
+/// expr  = and ("or" and)*
+/// and   = unary ("and" unary)*
+/// unary = "not" unary | "(" expr ")" | skip
+/// ```
+///
+/// where `skip` is a single skip condition like `weekday 6` or `date 2024-12-25`
+pub(crate) fn parse(expr: &str) -> Result<Skip, String> {
+    let spaced = expr.replace('(', " ( ").replace(')', " ) ");
+    let mut parser = Parser {
+        expr,
+        tokens: spaced.split_whitespace().collect(),
+        pos: 0,
+    };
+    let skip = parser.or()?;
+    match parser.peek() {
+        None => Ok(skip),
+        Some(token) => Err(format!("Unexpected '{token}' in skip expression '{expr}'")),
+    }
+}
+
+struct Parser<'a> {
+    expr: &'a str,
+    tokens: Vec<&'a str>,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&'a str> {
+        self.tokens.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Result<&'a str, String> {
+        let token = self
+            .peek()
+            .ok_or_else(|| format!("Unexpected end of skip expression '{}'", self.expr))?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn or(&mut self) -> Result<Skip, String> {
+        let mut any = vec![self.and()?];
+        while self.peek() == Some("or") {
+            self.pos += 1;
+            any.push(self.and()?);
+        }
+        Ok(if any.len() == 1 {
+            any.remove(0)
+        } else {
+            Skip::Any(any)
+        })
+    }
+
+    fn and(&mut self) -> Result<Skip, String> {
+        let mut all = vec![self.unary()?];
+        while self.peek() == Some("and") {
+            self.pos += 1;
+            all.push(self.unary()?);
+        }
+        Ok(if all.len() == 1 {
+            all.remove(0)
+        } else {
+            Skip::All(all)
+        })
+    }
+
+    fn unary(&mut self) -> Result<Skip, String> {
+        match self.next()? {
+            "not" => Ok(Skip::Not(Box::new(self.unary()?))),
+            "(" => {
+                let skip = self.or()?;
+                if self.next()? != ")" {
+                    return Err(format!(
+                        "Missing closing parenthesis in skip expression '{}'",
+                        self.expr
+                    ));
+                }
+                Ok(skip)
+            }
+            token @ (")" | "and" | "or") => Err(format!(
+                "Unexpected '{token}' in skip expression '{}'",
+                self.expr
+            )),
+            first => {
+                // a single skip runs up to the next operator or parenthesis
+                let mut words = vec![first];
+                while let Some(token) = self.peek()
+                    && !matches!(token, "and" | "or" | "not" | "(" | ")")
+                {
+                    words.push(token);
+                    self.pos += 1;
+                }
+                Task::parse_single_skip(&words.join(" "))
+            }
+        }
+    }
+}
@@ -1,6 +1,11 @@
+use crate::cron::CronExpr;
+use crate::schdule::MAX_DELAY;
 use async_trait::async_trait;
 use std::fmt::Debug;
-use time::{Date, OffsetDateTime, Time, UtcOffset, macros::format_description};
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+use std::time::Duration;
+use time::{Date, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset, macros::format_description};
 use tokio_util::sync::CancellationToken;
 
 /// a task that can be scheduled
@@ -9,6 +14,23 @@ pub trait Notifiable: Sync + Send + Debug {
     /// get the schedule type
     fn get_task(&self) -> Task;
 
+    /// the name of the task, reported by its [`TaskHandle`](crate::TaskHandle)
+    ///
+    /// Default is the type name without its module path
+    fn name(&self) -> String {
+        let name = std::any::type_name::<Self>();
+        let name = name.split('<').next().unwrap_or(name);
+        name.rsplit("::").next().unwrap_or(name).to_string()
+    }
+
+    /// tasks sharing a key never run their handlers at the same time, a trigger waits for
+    /// the running handler of another task with the same key
+    ///
+    /// Default is no key, the handler runs whenever triggered
+    fn single_flight_key(&self) -> Option<String> {
+        None
+    }
+
     /// called when the task is scheduled
     ///
     /// Default cancel on first trigger
@@ -16,17 +38,322 @@ pub trait Notifiable: Sync + Send + Debug {
         cancel.cancel();
     }
 
+    /// called when the task is scheduled, for handlers that can fail
+    ///
+    /// Default calls `on_time` and never fails
+    async fn on_time_fallible(&self, cancel: CancellationToken) -> Result<(), TaskError> {
+        self.on_time(cancel).await;
+        Ok(())
+    }
+
+    /// called when `on_time_fallible` returns an error
+    async fn on_error(&self, _err: &TaskError, _cancel: CancellationToken) {
+        // do nothing
+    }
+
+    /// called when the handler panics, with the panic message
+    ///
+    /// Default logs the panic and stops the task
+    async fn on_panic(&self, message: &str, _cancel: CancellationToken) -> PanicAction {
+        tracing::error!(message, "task stopped after a panic");
+        PanicAction::default()
+    }
+
+    /// called when the task is scheduled, returning a hint for the next delay of an
+    /// interval task
+    ///
+    /// the hint is clamped to [`Notifiable::delay_bounds`], `None` keeps the interval.
+    /// Default calls `on_time_fallible` without a hint
+    async fn on_time_adaptive(
+        &self,
+        cancel: CancellationToken,
+    ) -> Result<Option<Duration>, TaskError> {
+        self.on_time_fallible(cancel).await?;
+        Ok(None)
+    }
+
+    /// the bounds of the delay hints returned by `on_time_adaptive`
+    ///
+    /// Default is unbounded
+    fn delay_bounds(&self) -> RangeInclusive<Duration> {
+        Duration::ZERO..=Duration::MAX
+    }
+
     /// called when the task is skipped
     async fn on_skip(&self, _cancel: CancellationToken) {
         // do nothing
     }
+
+    /// called when the task is skipped, with the reason of the skip
+    ///
+    /// Default calls `on_skip`
+    async fn on_skip_with_reason(&self, cancel: CancellationToken, _reason: SkipReason) {
+        self.on_skip(cancel).await;
+    }
+
+    /// skip the first `n` triggers that would otherwise fire, e.g. to warm up
+    ///
+    /// triggers skipped by rules or as stale are not counted. Default is 0
+    fn skip_first(&self) -> u64 {
+        0
+    }
+
+    /// skip the triggers that came due while the handler was still running, calling the
+    /// skip handler with [`SkipReason::Overlap`] for each
+    ///
+    /// the handlers of a task never overlap, such triggers are otherwise dropped silently.
+    /// Interval tasks wait their interval after the handler returns and never have any.
+    /// Default is false
+    fn skip_if_running(&self) -> bool {
+        false
+    }
+
+    /// count only successful handler calls towards the cap of
+    /// [`Scheduler::run_for_fires`](crate::Scheduler::run_for_fires)
+    ///
+    /// by default every call counts, failed and panicked ones included; with this they
+    /// leave the cap untouched. Default is false
+    fn count_successes_only(&self) -> bool {
+        false
+    }
+
+    /// what an `At` task does when it is started after its time of the day
+    ///
+    /// Default waits for the next occurrence
+    fn at_behavior(&self) -> AtBehavior {
+        AtBehavior::default()
+    }
+
+    /// fire an interval task on clean boundaries of the clock, e.g. `interval(60)` at `:00`
+    /// each minute and `interval(900)` at `:00`, `:15`, `:30` and `:45`
+    ///
+    /// only possible for intervals dividing an hour, other intervals keep their phase.
+    /// Default keeps the phase of the start
+    fn align_interval(&self) -> bool {
+        false
+    }
+
+    /// skip a trigger that wakes up more than this late, e.g. after the runtime was blocked
+    ///
+    /// Default never skips late triggers
+    fn staleness(&self) -> Option<Duration> {
+        None
+    }
+
+    /// until the handler first succeeds, fire an interval task this often, then count its
+    /// interval from that success, e.g. a daily sync retried every minute until it works
+    ///
+    /// Default counts the interval from the start
+    fn retry_until_first_success(&self) -> Option<Duration> {
+        None
+    }
+
+    /// pause an interval task after consecutive failures of `on_time_fallible`
+    ///
+    /// Default never pauses
+    fn circuit_breaker(&self) -> Option<CircuitBreaker> {
+        None
+    }
+
+    /// called when the circuit breaker opens and the task pauses
+    async fn on_circuit_open(&self, _cancel: CancellationToken) {
+        // do nothing
+    }
+
+    /// called when the cooldown is over and the task resumes
+    async fn on_circuit_close(&self, _cancel: CancellationToken) {
+        // do nothing
+    }
+}
+
+/// forward every method of [`Notifiable`] through a pointer to a task
+macro_rules! forward_notifiable {
+    ($(#[$meta:meta])* $pointer:ident) => {
+        $(#[$meta])*
+        #[async_trait]
+        impl<T: Notifiable + ?Sized> Notifiable for $pointer<T> {
+            fn get_task(&self) -> Task {
+                (**self).get_task()
+            }
+
+            fn name(&self) -> String {
+                (**self).name()
+            }
+
+            fn single_flight_key(&self) -> Option<String> {
+                (**self).single_flight_key()
+            }
+
+            async fn on_time(&self, cancel: CancellationToken) {
+                (**self).on_time(cancel).await;
+            }
+
+            async fn on_time_fallible(&self, cancel: CancellationToken) -> Result<(), TaskError> {
+                (**self).on_time_fallible(cancel).await
+            }
+
+            async fn on_error(&self, err: &TaskError, cancel: CancellationToken) {
+                (**self).on_error(err, cancel).await;
+            }
+
+            async fn on_panic(&self, message: &str, cancel: CancellationToken) -> PanicAction {
+                (**self).on_panic(message, cancel).await
+            }
+
+            async fn on_time_adaptive(
+                &self,
+                cancel: CancellationToken,
+            ) -> Result<Option<Duration>, TaskError> {
+                (**self).on_time_adaptive(cancel).await
+            }
+
+            fn delay_bounds(&self) -> RangeInclusive<Duration> {
+                (**self).delay_bounds()
+            }
+
+            async fn on_skip(&self, cancel: CancellationToken) {
+                (**self).on_skip(cancel).await;
+            }
+
+            async fn on_skip_with_reason(&self, cancel: CancellationToken, reason: SkipReason) {
+                (**self).on_skip_with_reason(cancel, reason).await;
+            }
+
+            fn skip_first(&self) -> u64 {
+                (**self).skip_first()
+            }
+
+            fn skip_if_running(&self) -> bool {
+                (**self).skip_if_running()
+            }
+
+            fn count_successes_only(&self) -> bool {
+                (**self).count_successes_only()
+            }
+
+            fn at_behavior(&self) -> AtBehavior {
+                (**self).at_behavior()
+            }
+
+            fn align_interval(&self) -> bool {
+                (**self).align_interval()
+            }
+
+            fn staleness(&self) -> Option<Duration> {
+                (**self).staleness()
+            }
+
+            fn retry_until_first_success(&self) -> Option<Duration> {
+                (**self).retry_until_first_success()
+            }
+
+            fn circuit_breaker(&self) -> Option<CircuitBreaker> {
+                (**self).circuit_breaker()
+            }
+
+            async fn on_circuit_open(&self, cancel: CancellationToken) {
+                (**self).on_circuit_open(cancel).await;
+            }
+
+            async fn on_circuit_close(&self, cancel: CancellationToken) {
+                (**self).on_circuit_close(cancel).await;
+            }
+        }
+    };
 }
 
+forward_notifiable!(
+    /// a boxed task, e.g. `Box<dyn Notifiable>` to run tasks of mixed types, see
+    /// [`Scheduler::run_all_boxed`](crate::Scheduler::run_all_boxed)
+    Box
+);
+
+forward_notifiable!(
+    /// a shared task, e.g. to keep state the caller reads while the task runs
+    Arc
+);
+
+/// why a trigger was skipped, see [`Notifiable::on_skip_with_reason`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkipReason {
+    /// matched one of the skip rules of the task
+    Rule,
+    /// randomly skipped, see [`SchedulerBuilder::chaos_skip_probability`](crate::SchedulerBuilder::chaos_skip_probability)
+    Chaos,
+    /// the gate of the scheduler was closed, see [`SchedulerBuilder::gate`](crate::SchedulerBuilder::gate)
+    Gate,
+    /// woke up later than [`Notifiable::staleness`] allows
+    Stale,
+    /// the time of a `Once` task, or of today for an `At` task, had already passed when
+    /// it was started
+    Missed,
+    /// one of the first triggers, see [`Notifiable::skip_first`]
+    Warmup,
+    /// came due while the handler was still running, see [`Notifiable::skip_if_running`]
+    Overlap,
+}
+
+/// what a trigger would do, see [`Scheduler::simulate`](crate::Scheduler::simulate)
 #[derive(Debug, Clone, PartialEq)]
+pub enum FireOutcome {
+    /// the handler would be called
+    Fire,
+    /// the trigger would be skipped by this rule
+    Skip(Skip),
+}
+
+/// what an `At` task started after its time of the day does, see [`Notifiable::at_behavior`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AtBehavior {
+    /// skip the missed time of today with [`SkipReason::Missed`], then wait for the time
+    /// tomorrow
+    #[default]
+    NextOccurrence,
+    /// fire right away for the missed time of today, then wait for the time tomorrow
+    CatchUp,
+}
+
+/// what a task does after its handler panicked, see [`Notifiable::on_panic`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PanicAction {
+    /// cancel the task
+    #[default]
+    Stop,
+    /// keep the task scheduled, the panicked trigger counts as failed
+    Continue,
+}
+
+/// the error returned by a failing [`Notifiable::on_time_fallible`]
+pub type TaskError = Box<dyn std::error::Error + Send + Sync>;
+
+/// circuit breaker settings for interval tasks
+///
+/// after `threshold` consecutive failures the task pauses for `cooldown` instead of
+/// waiting for the next interval, then retries. A success resets the count, a failure
+/// right after the cooldown opens the circuit again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CircuitBreaker {
+    pub threshold: u32,
+    pub cooldown: Duration,
+}
+
+impl CircuitBreaker {
+    pub fn new(threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            threshold,
+            cooldown,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Skip {
     /// skip fixed date
     Date(Date),
-    /// skip date range
+    /// skip date range, both ends included
+    ///
+    /// start must not be after end, see [`Skip::date_range`]
     DateRange(Date, Date),
     /// skip days
     ///
@@ -34,24 +361,69 @@ pub enum Skip {
     Day(Vec<u8>),
     /// skip days range
     ///
+    /// both ends are included, a start after the end wraps over the week
+    ///
     /// 1: Monday, 2: Tuesday, 3: Wednesday, 4: Thursday, 5: Friday, 6: Saturday, 7: Sunday
     DayRange(usize, usize),
     /// skip fixed time
     Time(Time),
     /// skip time range
     ///
-    /// end must be greater than start
+    /// both ends are included, a start after the end wraps over midnight
     TimeRange(Time, Time),
+    /// skip time range excluding its end, e.g. `[09:00, 17:00)`
+    ///
+    /// a start after the end wraps over midnight
+    TimeRangeExclusive(Time, Time),
+    /// skip the first calendar day of the month
+    FirstOfMonth,
+    /// skip the last calendar day of the month
+    ///
+    /// the month length is taken from the year, so February 29 is the last day in leap years
+    LastOfMonth,
+    /// skip months
+    ///
+    /// 1: January to 12: December
+    Month(Vec<u8>),
+    /// skip months range
+    ///
+    /// both ends are included, a start after the end wraps over the year, e.g. November
+    /// to February
+    MonthRange(u8, u8),
+    /// skip the minutes on a grid of `step` minutes anchored at a time
+    ///
+    /// a time matches when its minute of the day is a whole number of steps before or
+    /// after the anchor minute on the same day; seconds are ignored and a step of 0
+    /// only matches the anchor minute, e.g. `TimeGrid(time!(00:00), 30)` skips
+    /// every `:00` and `:30`
+    TimeGrid(Time, u32),
+    /// skip every day except these
+    ///
+    /// 1: Monday, 2: Tuesday, 3: Wednesday, 4: Thursday, 5: Friday, 6: Saturday, 7: Sunday
+    OnlyDay(Vec<u8>),
+    /// skip every day except the `week`th `weekday` of the month, e.g. to run on the
+    /// second Tuesday or the last Friday
+    ///
+    /// a positive `week` counts from the start of the month, 1 is the first, a negative one
+    /// from the end, -1 is the last. A week of 0 or beyond the month skips every day.
+    ///
+    /// 1: Monday, 2: Tuesday, 3: Wednesday, 4: Thursday, 5: Friday, 6: Saturday, 7: Sunday
+    NthWeekday(i8, u8),
+    /// skip every time outside of the range
+    ///
+    /// both ends are allowed, a start after the end wraps over midnight
+    OnlyTimeRange(Time, Time),
+    /// skip when all of the rules match
+    All(Vec<Skip>),
+    /// skip when any of the rules matches
+    Any(Vec<Skip>),
+    /// skip when the rule does not match
+    Not(Box<Skip>),
     /// no skip
+    #[default]
     None,
 }
 
-impl Default for Skip {
-    fn default() -> Self {
-        Self::None
-    }
-}
-
 impl std::fmt::Display for Skip {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -61,12 +433,71 @@ impl std::fmt::Display for Skip {
             Skip::DayRange(start, end) => write!(f, "day range: {start} - {end}"),
             Skip::Time(time) => write!(f, "time: {time}"),
             Skip::TimeRange(start, end) => write!(f, "time range: {start} - {end}"),
+            Skip::TimeRangeExclusive(start, end) => {
+                write!(f, "time range: {start} - {end} (exclusive)")
+            }
+            Skip::FirstOfMonth => write!(f, "first of month"),
+            Skip::LastOfMonth => write!(f, "last of month"),
+            Skip::Month(month) => write!(f, "month: {month:?}"),
+            Skip::MonthRange(start, end) => write!(f, "month range: {start} - {end}"),
+            Skip::TimeGrid(anchor, step) => write!(f, "time grid: every {step}m from {anchor}"),
+            Skip::OnlyDay(day) => write!(f, "only day: {day:?}"),
+            Skip::NthWeekday(week, weekday) => write!(f, "nth weekday: {weekday} in week {week}"),
+            Skip::OnlyTimeRange(start, end) => write!(f, "only time range: {start} - {end}"),
+            Skip::All(all) => write_joined(f, all, " and "),
+            Skip::Any(any) => write_joined(f, any, " or "),
+            Skip::Not(skip) => write!(f, "not {skip}"),
             Skip::None => write!(f, "none"),
         }
     }
 }
 
+/// write the rules in parentheses, separated by `separator`
+fn write_joined(
+    f: &mut std::fmt::Formatter<'_>,
+    skips: &[Skip],
+    separator: &str,
+) -> std::fmt::Result {
+    write!(f, "(")?;
+    for (i, skip) in skips.iter().enumerate() {
+        if i > 0 {
+            write!(f, "{separator}")?;
+        }
+        write!(f, "{skip}")?;
+    }
+    write!(f, ")")
+}
+
 impl Skip {
+    /// parse a boolean expression over skip conditions into a tree of
+    /// [`Skip::All`], [`Skip::Any`] and [`Skip::Not`]
+    ///
+    /// `not` binds tighter than `and`, which binds tighter than `or`, parentheses group.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use easy_schedule::Skip;
+    ///
+    /// let skip = Skip::parse_expr("weekday 6,7 or (date 2024-12-25)").unwrap();
+    /// let skip = Skip::parse_expr("not weekday 1..5 and time 09:00..17:00").unwrap();
+    /// ```
+    pub fn parse_expr(expr: &str) -> Result<Self, String> {
+        crate::skip_expr::parse(expr)
+    }
+
+    /// create a [`Skip::DateRange`], rejecting a start after the end
+    ///
+    /// a reversed `DateRange` never matches any date
+    pub fn date_range(start: Date, end: Date) -> Result<Self, String> {
+        if start > end {
+            return Err(format!(
+                "Invalid date range: start {start} is after end {end}"
+            ));
+        }
+        Ok(Skip::DateRange(start, end))
+    }
+
     /// check if the time is skipped
     pub fn is_skip(&self, time: OffsetDateTime) -> bool {
         match self {
@@ -75,7 +506,12 @@ impl Skip {
             Skip::Day(day) => day.contains(&(time.weekday().number_from_monday())),
             Skip::DayRange(start, end) => {
                 let weekday = time.weekday().number_from_monday() as usize;
-                weekday >= *start && weekday <= *end
+                if start <= end {
+                    weekday >= *start && weekday <= *end
+                } else {
+                    // wraps over the week, e.g. Saturday to Monday
+                    weekday >= *start || weekday <= *end
+                }
             }
             Skip::Time(skip_time) => time.time() == *skip_time,
             Skip::TimeRange(start, end) => {
@@ -88,21 +524,193 @@ impl Skip {
                     current_time >= *start || current_time <= *end
                 }
             }
+            Skip::TimeRangeExclusive(start, end) => {
+                let current_time = time.time();
+                if start <= end {
+                    current_time >= *start && current_time < *end
+                } else {
+                    current_time >= *start || current_time < *end
+                }
+            }
+            Skip::FirstOfMonth => time.day() == 1,
+            Skip::LastOfMonth => time.day() == time.month().length(time.year()),
+            Skip::Month(month) => month.contains(&(time.month() as u8)),
+            Skip::MonthRange(start, end) => {
+                let month = time.month() as u8;
+                if start <= end {
+                    month >= *start && month <= *end
+                } else {
+                    // wraps over the year, e.g. November to February
+                    month >= *start || month <= *end
+                }
+            }
+            Skip::TimeGrid(anchor, step) => {
+                let minute_of_day = |t: Time| t.hour() as i64 * 60 + t.minute() as i64;
+                let offset = minute_of_day(time.time()) - minute_of_day(*anchor);
+                if *step == 0 {
+                    offset == 0
+                } else {
+                    offset.rem_euclid(*step as i64) == 0
+                }
+            }
+            Skip::OnlyDay(day) => !Skip::Day(day.clone()).is_skip(time),
+            Skip::NthWeekday(week, weekday) => {
+                if time.weekday().number_from_monday() != *weekday {
+                    return true;
+                }
+                // the week of the month the day falls in, from the start or from the end
+                let week = *week as i16;
+                let day = time.day() as i16;
+                let length = time.month().length(time.year()) as i16;
+                let nth = if week > 0 {
+                    (day - 1) / 7 + 1
+                } else {
+                    -((length - day) / 7 + 1)
+                };
+                nth != week
+            }
+            Skip::OnlyTimeRange(start, end) => !Skip::TimeRange(*start, *end).is_skip(time),
+            Skip::All(all) => all.iter().all(|skip| skip.is_skip(time)),
+            Skip::Any(any) => any.iter().any(|skip| skip.is_skip(time)),
+            Skip::Not(skip) => !skip.is_skip(time),
             Skip::None => false,
         }
     }
 }
 
-#[derive(Debug, Clone)]
+/// computes the fire time of a day from its date and the scheduler timezone offset in minutes
+pub type DailyTime = Arc<dyn Fn(Date, i16) -> Time + Send + Sync>;
+
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Task {
     /// wait seconds
     Wait(u64, Option<Vec<Skip>>),
     /// interval seconds
     Interval(u64, Option<Vec<Skip>>),
+    /// wait milliseconds
+    WaitMillis(u64, Option<Vec<Skip>>),
+    /// interval milliseconds
+    IntervalMillis(u64, Option<Vec<Skip>>),
+    /// interval seconds at a fixed rate from the start of the task
+    ///
+    /// the wait does not include the run time of the handler, so a slow handler does not
+    /// shift later triggers. Triggers missed while the handler is still running are dropped
+    FixedRate(u64, Option<Vec<Skip>>),
+    /// interval seconds on a grid anchored at a fixed time
+    ///
+    /// fires at `anchor + k * interval`, so restarts keep the same phase
+    IntervalPhased(u64, OffsetDateTime, Option<Vec<Skip>>),
     /// at time
     At(Time, Option<Vec<Skip>>),
+    /// at time every day up to and including the date
+    AtUntil(Time, Date, Option<Vec<Skip>>),
     /// exact time
     Once(OffsetDateTime, Option<Vec<Skip>>),
+    /// every hour at minute
+    Hourly(u8, Option<Vec<Skip>>),
+    /// at time on days of the week
+    ///
+    /// 1: Monday, 2: Tuesday, 3: Wednesday, 4: Thursday, 5: Friday, 6: Saturday, 7: Sunday
+    Weekly(Vec<u8>, Time, Option<Vec<Skip>>),
+    /// at time every `n` business days
+    ///
+    /// business days are Monday to Friday, days matched by the skip rules at that time,
+    /// e.g. holidays, are not counted. `n` of 0 is treated as 1
+    BusinessDayInterval(u32, Time, Option<Vec<Skip>>),
+    /// cron expression, e.g. `0 30 9 * * 1-5`
+    ///
+    /// five fields `minute hour day-of-month month day-of-week` or six with a leading
+    /// `second`, evaluated in the scheduler timezone
+    Cron(String, Option<Vec<Skip>>),
+    /// at several times each day
+    AtMulti(Vec<Time>, Option<Vec<Skip>>),
+    /// at a time computed for each day, e.g. sunrise
+    ///
+    /// the closure is called with the date and the timezone offset in minutes of the scheduler,
+    /// it can not be serialized
+    #[cfg_attr(feature = "serde", serde(skip))]
+    AtComputed(DailyTime, Option<Vec<Skip>>),
+    /// a task that only fires from `start` until `end`, `end` excluded, see [`Task::within`]
+    Within(Box<Task>, OffsetDateTime, OffsetDateTime),
+    /// once on a date at a time, in the scheduler timezone
+    OnceOn(Date, Time, Option<Vec<Skip>>),
+}
+
+impl std::fmt::Debug for Task {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Task::Wait(wait, skip) => f.debug_tuple("Wait").field(wait).field(skip).finish(),
+            Task::Interval(interval, skip) => f
+                .debug_tuple("Interval")
+                .field(interval)
+                .field(skip)
+                .finish(),
+            Task::WaitMillis(wait, skip) => {
+                f.debug_tuple("WaitMillis").field(wait).field(skip).finish()
+            }
+            Task::IntervalMillis(interval, skip) => f
+                .debug_tuple("IntervalMillis")
+                .field(interval)
+                .field(skip)
+                .finish(),
+            Task::FixedRate(interval, skip) => f
+                .debug_tuple("FixedRate")
+                .field(interval)
+                .field(skip)
+                .finish(),
+            Task::IntervalPhased(interval, anchor, skip) => f
+                .debug_tuple("IntervalPhased")
+                .field(interval)
+                .field(anchor)
+                .field(skip)
+                .finish(),
+            Task::At(time, skip) => f.debug_tuple("At").field(time).field(skip).finish(),
+            Task::AtUntil(time, until, skip) => f
+                .debug_tuple("AtUntil")
+                .field(time)
+                .field(until)
+                .field(skip)
+                .finish(),
+            Task::AtMulti(times, skip) => {
+                f.debug_tuple("AtMulti").field(times).field(skip).finish()
+            }
+            Task::Once(time, skip) => f.debug_tuple("Once").field(time).field(skip).finish(),
+            Task::OnceOn(date, time, skip) => f
+                .debug_tuple("OnceOn")
+                .field(date)
+                .field(time)
+                .field(skip)
+                .finish(),
+            Task::Within(task, start, end) => f
+                .debug_tuple("Within")
+                .field(task)
+                .field(start)
+                .field(end)
+                .finish(),
+            Task::Hourly(minute, skip) => {
+                f.debug_tuple("Hourly").field(minute).field(skip).finish()
+            }
+            Task::Weekly(days, time, skip) => f
+                .debug_tuple("Weekly")
+                .field(days)
+                .field(time)
+                .field(skip)
+                .finish(),
+            Task::BusinessDayInterval(n, time, skip) => f
+                .debug_tuple("BusinessDayInterval")
+                .field(n)
+                .field(time)
+                .field(skip)
+                .finish(),
+            Task::Cron(expr, skip) => f.debug_tuple("Cron").field(expr).field(skip).finish(),
+            Task::AtComputed(_, skip) => f
+                .debug_tuple("AtComputed")
+                .field(&format_args!("<fn>"))
+                .field(skip)
+                .finish(),
+        }
+    }
 }
 
 impl PartialEq for Task {
@@ -110,8 +718,41 @@ impl PartialEq for Task {
         match (self, other) {
             (Task::Wait(a, skip_a), Task::Wait(b, skip_b)) => a == b && skip_a == skip_b,
             (Task::Interval(a, skip_a), Task::Interval(b, skip_b)) => a == b && skip_a == skip_b,
+            (Task::WaitMillis(a, skip_a), Task::WaitMillis(b, skip_b)) => {
+                a == b && skip_a == skip_b
+            }
+            (Task::IntervalMillis(a, skip_a), Task::IntervalMillis(b, skip_b)) => {
+                a == b && skip_a == skip_b
+            }
+            (Task::FixedRate(a, skip_a), Task::FixedRate(b, skip_b)) => a == b && skip_a == skip_b,
+            (
+                Task::IntervalPhased(a, anchor_a, skip_a),
+                Task::IntervalPhased(b, anchor_b, skip_b),
+            ) => a == b && anchor_a == anchor_b && skip_a == skip_b,
             (Task::At(a, skip_a), Task::At(b, skip_b)) => a == b && skip_a == skip_b,
+            (Task::AtMulti(a, skip_a), Task::AtMulti(b, skip_b)) => a == b && skip_a == skip_b,
+            (Task::Within(a, start_a, end_a), Task::Within(b, start_b, end_b)) => {
+                a == b && start_a == start_b && end_a == end_b
+            }
+            (Task::AtUntil(a, until_a, skip_a), Task::AtUntil(b, until_b, skip_b)) => {
+                a == b && until_a == until_b && skip_a == skip_b
+            }
             (Task::Once(a, skip_a), Task::Once(b, skip_b)) => a == b && skip_a == skip_b,
+            (Task::OnceOn(date_a, a, skip_a), Task::OnceOn(date_b, b, skip_b)) => {
+                date_a == date_b && a == b && skip_a == skip_b
+            }
+            (Task::Hourly(a, skip_a), Task::Hourly(b, skip_b)) => a == b && skip_a == skip_b,
+            (Task::Weekly(days_a, a, skip_a), Task::Weekly(days_b, b, skip_b)) => {
+                days_a == days_b && a == b && skip_a == skip_b
+            }
+            (
+                Task::BusinessDayInterval(n_a, a, skip_a),
+                Task::BusinessDayInterval(n_b, b, skip_b),
+            ) => n_a == n_b && a == b && skip_a == skip_b,
+            (Task::Cron(a, skip_a), Task::Cron(b, skip_b)) => a == b && skip_a == skip_b,
+            (Task::AtComputed(a, skip_a), Task::AtComputed(b, skip_b)) => {
+                Arc::ptr_eq(a, b) && skip_a == skip_b
+            }
             _ => false,
         }
     }
@@ -119,23 +760,38 @@ impl PartialEq for Task {
 
 impl Task {
     /// get the next run time for the scheduled task
+    ///
+    /// a `Wait` or `Interval` longer than about 30 years is clamped to that, as the
+    /// scheduler does
     pub fn get_next_run_time<T: Notifiable + 'static>(
         &self,
         timezone_minutes: i16,
     ) -> Option<OffsetDateTime> {
         let now = get_now(timezone_minutes).unwrap_or_else(|_| OffsetDateTime::now_utc());
+        let offset = now.offset();
+        self.next_run_time_from(now, |time| time.to_offset(offset))
+    }
 
+    /// the next run time after `now`, with the skip rules evaluated on `local` of each run
+    pub(crate) fn next_run_time_from(
+        &self,
+        now: OffsetDateTime,
+        local: impl Fn(OffsetDateTime) -> OffsetDateTime,
+    ) -> Option<OffsetDateTime> {
         match self.clone() {
             Task::Wait(wait, skip) => {
-                let mut next_time = now + time::Duration::seconds(wait as i64);
+                // clamped like the delay the scheduler sleeps for
+                let step = Duration::from_secs(wait).min(MAX_DELAY);
+                let mut next_time = add_delay(now, step)?;
 
                 if let Some(skip_rules) = skip {
                     let mut attempts = 0;
                     const MAX_ATTEMPTS: u32 = 1000;
 
-                    while skip_rules.iter().any(|s| s.is_skip(next_time)) && attempts < MAX_ATTEMPTS
+                    while skip_rules.iter().any(|s| s.is_skip(local(next_time)))
+                        && attempts < MAX_ATTEMPTS
                     {
-                        next_time += time::Duration::seconds(wait as i64);
+                        next_time = add_delay(next_time, step)?;
                         attempts += 1;
                     }
 
@@ -147,15 +803,18 @@ impl Task {
                 Some(next_time)
             }
             Task::Interval(interval, skip) => {
-                let mut next_time = now + time::Duration::seconds(interval as i64);
+                // clamped like the delay the scheduler sleeps for
+                let step = Duration::from_secs(interval).min(MAX_DELAY);
+                let mut next_time = add_delay(now, step)?;
 
                 if let Some(skip_rules) = skip {
                     let mut attempts = 0;
                     const MAX_ATTEMPTS: u32 = 1000;
 
-                    while skip_rules.iter().any(|s| s.is_skip(next_time)) && attempts < MAX_ATTEMPTS
+                    while skip_rules.iter().any(|s| s.is_skip(local(next_time)))
+                        && attempts < MAX_ATTEMPTS
                     {
-                        next_time += time::Duration::seconds(interval as i64);
+                        next_time = add_delay(next_time, step)?;
                         attempts += 1;
                     }
 
@@ -173,36 +832,487 @@ impl Task {
                     let mut attempts = 0;
                     const MAX_ATTEMPTS: u32 = 365;
 
-                    while skip_rules.iter().any(|s| s.is_skip(next_time)) && attempts < MAX_ATTEMPTS
+                    while skip_rules.iter().any(|s| s.is_skip(local(next_time)))
+                        && attempts < MAX_ATTEMPTS
                     {
                         next_time += time::Duration::days(1);
                         attempts += 1;
                     }
 
-                    if attempts >= MAX_ATTEMPTS {
-                        return None;
-                    }
-                }
+                    if attempts >= MAX_ATTEMPTS {
+                        return None;
+                    }
+                }
+
+                Some(next_time)
+            }
+            Task::Once(once_time, skip) => {
+                if once_time <= now {
+                    return None;
+                }
+
+                if let Some(skip_rules) = skip
+                    && skip_rules.iter().any(|s| s.is_skip(local(once_time)))
+                {
+                    return None;
+                }
+
+                Some(once_time)
+            }
+            Task::WaitMillis(..)
+            | Task::IntervalMillis(..)
+            | Task::FixedRate(..)
+            | Task::IntervalPhased(..)
+            | Task::AtUntil(..)
+            | Task::AtMulti(..)
+            | Task::Hourly(..)
+            | Task::Weekly(..)
+            | Task::BusinessDayInterval(..)
+            | Task::Cron(..)
+            | Task::AtComputed(..)
+            | Task::Within(..)
+            | Task::OnceOn(..) => {
+                const MAX_ATTEMPTS: usize = 24 * 366;
+                self.run_times(now)
+                    .take(MAX_ATTEMPTS)
+                    .find(|time| !self.is_skipped(local(*time)))
+            }
+        }
+    }
+}
+
+/// builder for a [`Task`] with skip rules, created by [`Task::wait`], [`Task::interval`],
+/// [`Task::at`] or [`Task::once`]
+///
+/// e.g. `Task::interval(300).skip_days([6, 7]).build()`
+#[derive(Debug, Clone)]
+pub struct TaskBuilder {
+    task: Task,
+    skips: Vec<Skip>,
+}
+
+impl TaskBuilder {
+    fn new(task: Task) -> Self {
+        Self {
+            task,
+            skips: Vec::new(),
+        }
+    }
+
+    /// add a skip rule
+    pub fn skip(mut self, skip: Skip) -> Self {
+        self.skips.push(skip);
+        self
+    }
+
+    /// skip the weekdays `days`, 1 is Monday, see [`Skip::Day`]
+    pub fn skip_days(self, days: impl IntoIterator<Item = u8>) -> Self {
+        self.skip(Skip::Day(days.into_iter().collect()))
+    }
+
+    /// skip the weekdays from `start` to `end`, see [`Skip::DayRange`]
+    pub fn skip_day_range(self, start: usize, end: usize) -> Self {
+        self.skip(Skip::DayRange(start, end))
+    }
+
+    /// skip a date, see [`Skip::Date`]
+    pub fn skip_date(self, date: Date) -> Self {
+        self.skip(Skip::Date(date))
+    }
+
+    /// skip the dates from `start` to `end`, see [`Skip::DateRange`]
+    pub fn skip_date_range(self, start: Date, end: Date) -> Self {
+        self.skip(Skip::DateRange(start, end))
+    }
+
+    /// skip a time of the day, see [`Skip::Time`]
+    pub fn skip_time(self, time: Time) -> Self {
+        self.skip(Skip::Time(time))
+    }
+
+    /// skip the times of the day from `start` to `end`, see [`Skip::TimeRange`]
+    pub fn skip_time_range(self, start: Time, end: Time) -> Self {
+        self.skip(Skip::TimeRange(start, end))
+    }
+
+    /// the task, without skip rules if none were added
+    pub fn build(self) -> Task {
+        let skips = (!self.skips.is_empty()).then_some(self.skips);
+        match self.task {
+            Task::Wait(seconds, _) => Task::Wait(seconds, skips),
+            Task::Interval(seconds, _) => Task::Interval(seconds, skips),
+            Task::At(time, _) => Task::At(time, skips),
+            Task::Once(at, _) => Task::Once(at, skips),
+            task => task,
+        }
+    }
+}
+
+impl From<TaskBuilder> for Task {
+    fn from(builder: TaskBuilder) -> Self {
+        builder.build()
+    }
+}
+
+impl Task {
+    /// build a `Wait` task firing once after `seconds`
+    pub fn wait(seconds: u64) -> TaskBuilder {
+        TaskBuilder::new(Task::Wait(seconds, None))
+    }
+
+    /// build an `Interval` task firing every `seconds`
+    pub fn interval(seconds: u64) -> TaskBuilder {
+        TaskBuilder::new(Task::Interval(seconds, None))
+    }
+
+    /// build an `At` task firing daily at `time`
+    pub fn at(time: Time) -> TaskBuilder {
+        TaskBuilder::new(Task::At(time, None))
+    }
+
+    /// build a `Once` task firing at `at`
+    pub fn once(at: OffsetDateTime) -> TaskBuilder {
+        TaskBuilder::new(Task::Once(at, None))
+    }
+
+    /// a daily `At` task `offset` from `base`, wrapping over midnight
+    ///
+    /// e.g. `Task::at_offset(time!(09:00), -15.minutes())` fires at 08:45
+    pub fn at_offset(base: Time, offset: time::Duration) -> Task {
+        Task::At(base + offset, None)
+    }
+
+    /// limit the task to the window from `start` until `end`, `end` excluded
+    ///
+    /// nothing fires before `start`, and the task is done once `end` is reached
+    pub fn within(self, start: OffsetDateTime, end: OffsetDateTime) -> Task {
+        Task::Within(Box::new(self), start, end)
+    }
+
+    /// the task a [`Task::Within`] limits, the task itself otherwise
+    pub(crate) fn without_window(self) -> Task {
+        match self {
+            Task::Within(task, ..) => task.without_window(),
+            task => task,
+        }
+    }
+
+    /// the skip rules of the task
+    pub(crate) fn skips(&self) -> &[Skip] {
+        match self {
+            Task::Wait(_, skip)
+            | Task::Interval(_, skip)
+            | Task::WaitMillis(_, skip)
+            | Task::IntervalMillis(_, skip)
+            | Task::FixedRate(_, skip)
+            | Task::IntervalPhased(_, _, skip)
+            | Task::At(_, skip)
+            | Task::AtUntil(_, _, skip)
+            | Task::AtMulti(_, skip)
+            | Task::Once(_, skip)
+            | Task::Hourly(_, skip)
+            | Task::Weekly(_, _, skip)
+            | Task::OnceOn(_, _, skip)
+            | Task::BusinessDayInterval(_, _, skip)
+            | Task::Cron(_, skip)
+            | Task::AtComputed(_, skip) => skip.as_deref().unwrap_or_default(),
+            Task::Within(task, ..) => task.skips(),
+        }
+    }
+
+    /// check if a trigger at `time` is skipped by any of the skip rules
+    pub(crate) fn is_skipped(&self, time: OffsetDateTime) -> bool {
+        self.skips().iter().any(|s| s.is_skip(time))
+    }
+
+    /// iterate over the times the task is triggered after `now`, skipped triggers included
+    ///
+    /// `now` should be in the scheduler timezone, since `At` tasks are resolved in its offset
+    pub fn run_times(&self, now: OffsetDateTime) -> RunTimes<'_> {
+        let first = match self {
+            Task::Wait(wait, _) => add_delay(now, Duration::from_secs(*wait)),
+            Task::Interval(interval, _) | Task::FixedRate(interval, _) => {
+                add_delay(now, Duration::from_secs(*interval))
+            }
+            Task::WaitMillis(wait, _) => add_delay(now, Duration::from_millis(*wait)),
+            Task::IntervalMillis(interval, _) => add_delay(now, Duration::from_millis(*interval)),
+            Task::IntervalPhased(interval, anchor, _) => i64::try_from(*interval)
+                .ok()
+                .filter(|interval| *interval > 0)
+                .and_then(|interval| {
+                    // the last grid point at or before now, then step past it if needed
+                    let steps = (now - *anchor).whole_seconds().div_euclid(interval);
+                    let next = anchor.checked_add(time::Duration::seconds(steps * interval))?;
+                    if next < now {
+                        next.checked_add(time::Duration::seconds(interval))
+                    } else {
+                        Some(next)
+                    }
+                }),
+            Task::At(time, _) => Some(get_next_time(now, *time)),
+            Task::AtUntil(time, until, _) => {
+                Some(get_next_time(now, *time)).filter(|next| next.date() <= *until)
+            }
+            Task::Once(once_time, _) => (*once_time > now).then_some(*once_time),
+            Task::OnceOn(date, time, _) => {
+                let at = date.with_time(*time).assume_offset(now.offset());
+                (at > now).then_some(at)
+            }
+            Task::Hourly(minute, _) => {
+                let next = now
+                    .replace_second(0)
+                    .and_then(|next| next.replace_nanosecond(0))
+                    .and_then(|next| next.replace_minute(*minute))
+                    .ok();
+                next.map(|next| {
+                    if next < now {
+                        next + time::Duration::hours(1)
+                    } else {
+                        next
+                    }
+                })
+            }
+            Task::AtMulti(times, _) => times.iter().map(|time| get_next_time(now, *time)).min(),
+            Task::Weekly(days, time, _) => next_weekday(get_next_time(now, *time), days),
+            Task::BusinessDayInterval(_, time, _) => self.business_day(get_next_time(now, *time)),
+            Task::Cron(expr, _) => CronExpr::parse(expr)
+                .ok()
+                .and_then(|cron| cron.next_from(now)),
+            Task::AtComputed(compute, _) => {
+                let today = computed_time(compute, now.date(), now.offset());
+                if today < now {
+                    let tomorrow = now.date().next_day();
+                    tomorrow.map(|date| computed_time(compute, date, now.offset()))
+                } else {
+                    Some(today)
+                }
+            }
+            Task::Within(task, start, end) => task
+                .run_times(now.max(*start))
+                .next()
+                .filter(|next| next < end),
+        };
+        RunTimes::new(self, first)
+    }
+
+    /// the most recent trigger at or before `now` that is not skipped
+    ///
+    /// `None` for tasks without a fixed phase, `Wait`, `Interval`, `FixedRate` and
+    /// `BusinessDayInterval`, and for a `Once` task still to come. `now` should be in the
+    /// scheduler timezone
+    pub fn previous_run_time(&self, now: OffsetDateTime) -> Option<OffsetDateTime> {
+        // look back over growing windows, so dense schedules stay cheap
+        const WINDOWS: [time::Duration; 5] = [
+            time::Duration::MINUTE,
+            time::Duration::HOUR,
+            time::Duration::DAY,
+            time::Duration::WEEK,
+            time::Duration::days(366),
+        ];
+        match self {
+            Task::Wait(..)
+            | Task::WaitMillis(..)
+            | Task::Interval(..)
+            | Task::IntervalMillis(..)
+            | Task::FixedRate(..)
+            | Task::BusinessDayInterval(..) => None,
+            Task::Once(at, _) => (*at <= now && !self.is_skipped(*at)).then_some(*at),
+            Task::OnceOn(date, time, _) => {
+                let at = date.with_time(*time).assume_offset(now.offset());
+                (at <= now && !self.is_skipped(at)).then_some(at)
+            }
+            // the end is excluded
+            Task::Within(task, start, end) => task
+                .previous_run_time(now.min(end.checked_sub(time::Duration::NANOSECOND)?))
+                .filter(|time| time >= start),
+            Task::IntervalPhased(..)
+            | Task::At(..)
+            | Task::AtUntil(..)
+            | Task::AtMulti(..)
+            | Task::Hourly(..)
+            | Task::Weekly(..)
+            | Task::Cron(..)
+            | Task::AtComputed(..) => WINDOWS.iter().find_map(|window| {
+                self.run_times(now - *window)
+                    .take_while(|time| *time <= now)
+                    .filter(|time| !self.is_skipped(*time))
+                    .last()
+            }),
+        }
+    }
+
+    /// like [`Task::run_times`] in an IANA timezone, times of the day keep their local
+    /// time across daylight saving changes
+    #[cfg(feature = "tz")]
+    pub fn run_times_in_zone(
+        &self,
+        now: OffsetDateTime,
+        zone: chrono_tz::Tz,
+    ) -> impl Iterator<Item = OffsetDateTime> + '_ {
+        let now = now.to_offset(crate::zone::offset_at(zone, now));
+        let wall_clock = self.follows_wall_clock();
+        self.run_times(now).map(move |time| {
+            if wall_clock {
+                crate::zone::localize(zone, time)
+            } else {
+                time
+            }
+        })
+    }
+
+    /// check if the task runs at times of the day, rather than after fixed durations
+    #[cfg(feature = "tz")]
+    pub(crate) fn follows_wall_clock(&self) -> bool {
+        match self {
+            Task::Wait(..)
+            | Task::WaitMillis(..)
+            | Task::Interval(..)
+            | Task::IntervalMillis(..)
+            | Task::FixedRate(..)
+            | Task::IntervalPhased(..)
+            | Task::Once(..) => false,
+            Task::At(..)
+            | Task::AtUntil(..)
+            | Task::AtMulti(..)
+            | Task::OnceOn(..)
+            | Task::Hourly(..)
+            | Task::Weekly(..)
+            | Task::BusinessDayInterval(..)
+            | Task::Cron(..)
+            | Task::AtComputed(..) => true,
+            Task::Within(task, ..) => task.follows_wall_clock(),
+        }
+    }
+
+    /// describe when the task runs next after `now`, for display
+    ///
+    /// `in 45s` below a minute, `in 20m` below an hour, `in 3h 20m` later the same day,
+    /// `tomorrow at 09:00`, `in 5 days at 09:00` further out, and `never` if nothing is
+    /// left to run
+    pub fn humanize_next(&self, now: OffsetDateTime) -> String {
+        const MAX_ATTEMPTS: usize = 24 * 366;
+        let Some(next) = self
+            .run_times(now)
+            .take(MAX_ATTEMPTS)
+            .find(|time| !self.is_skipped(*time))
+        else {
+            return "never".to_string();
+        };
+        let next = next.to_offset(now.offset());
+        let gap = next - now;
+        let days = (next.date() - now.date()).whole_days();
+        let at = format!("{:02}:{:02}", next.hour(), next.minute());
+        if gap < time::Duration::MINUTE {
+            format!("in {}s", gap.whole_seconds().max(0))
+        } else if gap < time::Duration::HOUR {
+            format!("in {}m", gap.whole_minutes())
+        } else if days == 0 {
+            format!("in {}h {}m", gap.whole_hours(), gap.whole_minutes() % 60)
+        } else if days == 1 {
+            format!("tomorrow at {at}")
+        } else {
+            format!("in {days} days at {at}")
+        }
+    }
+
+    /// the first skip rule skipping a trigger at `time`, `None` if it fires
+    pub fn explain_skip(&self, time: OffsetDateTime) -> Option<&Skip> {
+        self.skips().iter().find(|s| s.is_skip(time))
+    }
 
-                Some(next_time)
-            }
-            Task::Once(once_time, skip) => {
-                if once_time <= now {
-                    return None;
+    /// the next `count` triggers from now as text, one per line, skipped ones marked with
+    /// the rule skipping them, e.g. `2025-01-04 Sat 09:00:00 skipped: day: [6, 7]`
+    ///
+    /// `offset` is the timezone offset in minutes, as in
+    /// [`Scheduler::with_timezone_minutes`](crate::Scheduler::with_timezone_minutes)
+    pub fn timeline(&self, count: usize, offset: i16) -> String {
+        let format = format_description!(
+            "[year]-[month]-[day] [weekday repr:short] [hour]:[minute]:[second]"
+        );
+        let now = get_now(offset).unwrap_or_else(|_| OffsetDateTime::now_utc());
+        let lines = self
+            .run_times(now)
+            .take(count)
+            .map(|time| {
+                let time = time.to_offset(now.offset());
+                let at = time.format(&format).unwrap_or_else(|_| time.to_string());
+                match self.explain_skip(time) {
+                    Some(skip) => format!("{at} skipped: {skip}"),
+                    None => at,
                 }
+            })
+            .collect::<Vec<_>>();
+        if lines.is_empty() && count > 0 {
+            return format!("{self} never fires");
+        }
+        lines.join("\n")
+    }
 
-                if let Some(skip_rules) = skip {
-                    if skip_rules.iter().any(|s| s.is_skip(once_time)) {
-                        return None;
-                    }
-                }
+    /// the first business day at or after `from`, keeping the time of day
+    fn business_day(&self, from: OffsetDateTime) -> Option<OffsetDateTime> {
+        const MAX_DAYS: i64 = 366;
+        (0..MAX_DAYS)
+            .map(|offset| from + time::Duration::days(offset))
+            .find(|day| day.weekday().number_from_monday() <= 5 && !self.is_skipped(*day))
+    }
 
-                Some(once_time)
+    /// the trigger following a trigger at `prev`, `None` if the task does not repeat
+    pub(crate) fn following(&self, prev: OffsetDateTime) -> Option<OffsetDateTime> {
+        match self {
+            Task::Wait(..) | Task::WaitMillis(..) | Task::Once(..) | Task::OnceOn(..) => None,
+            Task::Interval(interval, _)
+            | Task::FixedRate(interval, _)
+            | Task::IntervalPhased(interval, ..) => add_delay(prev, Duration::from_secs(*interval)),
+            Task::IntervalMillis(interval, _) => add_delay(prev, Duration::from_millis(*interval)),
+            Task::At(..) => Some(prev + time::Duration::days(1)),
+            Task::AtUntil(_, until, _) => {
+                Some(prev + time::Duration::days(1)).filter(|next| next.date() <= *until)
             }
+            Task::AtMulti(times, _) => times
+                .iter()
+                // strictly after `prev`, its own time is due tomorrow
+                .map(|time| get_next_time(prev + time::Duration::NANOSECOND, *time))
+                .min(),
+            Task::Hourly(..) => Some(prev + time::Duration::hours(1)),
+            Task::Weekly(days, ..) => next_weekday(prev + time::Duration::days(1), days),
+            Task::BusinessDayInterval(n, ..) => (0..(*n).max(1)).try_fold(prev, |day, _| {
+                self.business_day(day + time::Duration::days(1))
+            }),
+            Task::Cron(expr, _) => CronExpr::parse(expr)
+                .ok()?
+                .next_from(prev + time::Duration::SECOND),
+            Task::AtComputed(compute, _) => {
+                let date = prev.date().next_day()?;
+                Some(computed_time(compute, date, prev.offset()))
+            }
+            Task::Within(task, _, end) => task.following(prev).filter(|next| next < end),
         }
     }
 }
 
+/// iterator over the trigger times of a task, created by [`Task::run_times`]
+#[derive(Debug, Clone)]
+pub struct RunTimes<'a> {
+    task: &'a Task,
+    next: Option<OffsetDateTime>,
+}
+
+impl<'a> RunTimes<'a> {
+    pub(crate) fn new(task: &'a Task, first: Option<OffsetDateTime>) -> Self {
+        Self { task, next: first }
+    }
+}
+
+impl Iterator for RunTimes<'_> {
+    type Item = OffsetDateTime;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.next?;
+        self.next = self.task.following(current);
+        Some(current)
+    }
+}
+
 impl Task {
     /// Parse a task from a string with detailed error reporting.
     ///
@@ -218,8 +1328,67 @@ impl Task {
     ///     Err(err) => println!("Error: {}", err),
     /// }
     /// ```
+    ///
+    /// Whitespace around the arguments and a trailing `# comment` are ignored, e.g.
+    /// `wait( 10 , weekday 6 ) # weekdays only`.
+    ///
+    /// A `once` time without an offset is in the default scheduler timezone, +8, see
+    /// [`Task::parse_with_timezone_minutes`].
     pub fn parse(s: &str) -> Result<Self, String> {
+        Self::parse_with_timezone_minutes(s, 8 * 60)
+    }
+
+    /// Parse a task from a string like [`Task::parse`], requiring a unit on the delay of
+    /// `wait`, `interval` and `fixed_rate`, e.g. `wait(10s)` or `interval(500ms)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use easy_schedule::Task;
+    ///
+    /// assert_eq!(Task::parse_strict("wait(10s)"), Task::parse("wait(10)"));
+    /// assert!(Task::parse_strict("wait(10)").is_err());
+    /// ```
+    pub fn parse_strict(s: &str) -> Result<Self, String> {
+        let task = Self::parse(s)?;
         let s = s.trim();
+        if let Some((function_name, args)) = s.split_once('(')
+            && matches!(function_name.trim(), "wait" | "interval" | "fixed_rate")
+        {
+            let value = args.split([',', ')']).next().unwrap_or_default().trim();
+            if value.bytes().all(|b| b.is_ascii_digit() || b == b'.') {
+                let function_name = function_name.trim();
+                // fixed_rate only takes whole seconds
+                let expected = if function_name == "fixed_rate" {
+                    format!("'{value}s'")
+                } else {
+                    format!("e.g. '{value}s' or '{value}ms'")
+                };
+                return Err(format!(
+                    "Missing unit on '{value}' in {function_name}({value}). Expected {expected}"
+                ));
+            }
+        }
+        Ok(task)
+    }
+
+    /// Parse a task from a string, with the timezone offset in minutes of the scheduler
+    /// for a `once` time without an offset.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use easy_schedule::Task;
+    ///
+    /// let task = Task::parse_with_timezone_minutes("once(2024-12-31 23:59:59)", 0).unwrap();
+    /// ```
+    pub fn parse_with_timezone_minutes(s: &str, timezone_minutes: i16) -> Result<Self, String> {
+        // a trailing `# comment` is ignored
+        let s = s.split_once('#').map_or(s, |(task, _)| task).trim();
+
+        if s.starts_with('@') {
+            return Self::parse_alias(s);
+        }
 
         // Find the function name and arguments
         let open_paren = s.find('(').ok_or_else(|| {
@@ -233,47 +1402,208 @@ impl Task {
         if close_paren <= open_paren {
             return Err(format!("Invalid parentheses in: '{s}'"));
         }
+        if close_paren + 1 < s.len() {
+            return Err(format!(
+                "Unexpected '{}' after the closing parenthesis in: '{s}'",
+                &s[close_paren + 1..]
+            ));
+        }
 
         let function_name = s[..open_paren].trim();
         let args = s[open_paren + 1..close_paren].trim();
 
         // Parse arguments - check if there are skip conditions
-        let (primary_arg, skip_conditions) = Self::parse_arguments(args)?;
+        let (primary_arg, skip_conditions) = if function_name == "cron" {
+            Self::parse_cron_arguments(args)?
+        } else if function_name == "at" {
+            Self::parse_at_arguments(args)?
+        } else {
+            Self::parse_arguments(args)?
+        };
+        let (primary_arg, clauses) = Self::parse_clauses(&primary_arg)?;
+        if let Some((keyword, _)) = clauses.first()
+            && function_name != "at"
+        {
+            return Err(format!(
+                "'{keyword}' is not supported in {function_name}({args})"
+            ));
+        }
 
         match function_name {
-            "wait" => {
-                let seconds = primary_arg.parse::<u64>().map_err(|_| {
-                    format!("Invalid seconds value '{primary_arg}' in wait({primary_arg})")
-                })?;
-                Ok(Task::Wait(seconds, skip_conditions))
-            }
-            "interval" => {
-                let seconds = primary_arg.parse::<u64>().map_err(|_| {
-                    format!("Invalid seconds value '{primary_arg}' in interval({primary_arg})")
+            "wait" => match Self::parse_delay(&primary_arg) {
+                Some(Delay::Seconds(seconds)) => Ok(Task::Wait(seconds, skip_conditions)),
+                Some(Delay::Millis(millis)) => Ok(Task::WaitMillis(millis, skip_conditions)),
+                None => Err(format!(
+                    "Invalid seconds value '{primary_arg}' in wait({primary_arg})"
+                )),
+            },
+            "interval" => match Self::parse_delay(&primary_arg) {
+                Some(Delay::Seconds(seconds)) => Ok(Task::Interval(seconds, skip_conditions)),
+                Some(Delay::Millis(millis)) => Ok(Task::IntervalMillis(millis, skip_conditions)),
+                None => Err(format!(
+                    "Invalid seconds value '{primary_arg}' in interval({primary_arg})"
+                )),
+            },
+            "fixed_rate" => {
+                let seconds = primary_arg.strip_suffix('s').unwrap_or(&primary_arg);
+                let seconds = seconds.trim().parse::<u64>().map_err(|_| {
+                    format!("Invalid seconds value '{primary_arg}' in fixed_rate({primary_arg})")
                 })?;
-                Ok(Task::Interval(seconds, skip_conditions))
+                Ok(Task::FixedRate(seconds, skip_conditions))
             }
             "at" => {
-                let format = format_description!("[hour]:[minute]");
-                let time = Time::parse(&primary_arg, &format).map_err(|_| {
-                    format!("Invalid time format '{primary_arg}' in at({primary_arg}). Expected format: HH:MM")
-                })?;
-                Ok(Task::At(time, skip_conditions))
+                let mut times = primary_arg
+                    .split(',')
+                    .map(|time| {
+                        let time = time.trim();
+                        Self::parse_time_of_day(time).ok_or_else(|| {
+                                format!("Invalid time format '{time}' in at({primary_arg}). Expected format: HH:MM[:SS]")
+                            })
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                match (clauses.first(), times.len()) {
+                    (Some((_, until)), 1) => {
+                        let until = Self::parse_date(until)?;
+                        Ok(Task::AtUntil(times.remove(0), until, skip_conditions))
+                    }
+                    (Some((keyword, _)), _) => Err(format!(
+                        "'{keyword}' is not supported with several times in at({primary_arg})"
+                    )),
+                    (None, 1) => Ok(Task::At(times.remove(0), skip_conditions)),
+                    (None, _) => Ok(Task::AtMulti(times, skip_conditions)),
+                }
             }
             "once" => {
                 let format = format_description!(
                     "[year]-[month]-[day] [hour]:[minute]:[second] [offset_hour sign:mandatory]"
                 );
+                let format_minutes = format_description!(
+                    "[year]-[month]-[day] [hour]:[minute]:[second] [offset_hour sign:mandatory]:[offset_minute]"
+                );
+                let local = format_description!("[year]-[month]-[day] [hour]:[minute]:[second]");
                 let datetime = OffsetDateTime::parse(&primary_arg, &format)
-                    .map_err(|_| format!("Invalid datetime format '{primary_arg}' in once({primary_arg}). Expected format: YYYY-MM-DD HH:MM:SS +HH"))?;
+                    .or_else(|_| OffsetDateTime::parse(&primary_arg, &format_minutes))
+                    .ok()
+                    .or_else(|| {
+                        let offset =
+                            UtcOffset::from_whole_seconds(timezone_minutes as i32 * 60).ok()?;
+                        let datetime = PrimitiveDateTime::parse(&primary_arg, &local).ok()?;
+                        Some(datetime.assume_offset(offset))
+                    })
+                    .ok_or_else(|| format!("Invalid datetime format '{primary_arg}' in once({primary_arg}). Expected format: YYYY-MM-DD HH:MM:SS [+HH[:MM]]"))?;
                 Ok(Task::Once(datetime, skip_conditions))
             }
+            "onceon" => {
+                let (date, time) = primary_arg.split_once(" at ").ok_or_else(|| {
+                    format!(
+                        "Invalid onceon({primary_arg}). Expected format: YYYY-MM-DD at HH:MM[:SS]"
+                    )
+                })?;
+                let date = Self::parse_date(date.trim())?;
+                let time = Self::parse_time_of_day(time.trim()).ok_or_else(|| {
+                    format!("Invalid time format '{}' in onceon({primary_arg}). Expected format: HH:MM[:SS]", time.trim())
+                })?;
+                Ok(Task::OnceOn(date, time, skip_conditions))
+            }
+            "cron" => {
+                CronExpr::parse(&primary_arg)?;
+                Ok(Task::Cron(primary_arg, skip_conditions))
+            }
+            "hourly" => {
+                let minute = primary_arg
+                    .parse::<u8>()
+                    .ok()
+                    .filter(|minute| *minute < 60)
+                    .ok_or_else(|| {
+                        format!("Invalid minute value '{primary_arg}' in hourly({primary_arg}). Expected 0-59")
+                    })?;
+                Ok(Task::Hourly(minute, skip_conditions))
+            }
+            _ => Err(format!(
+                "Unknown task type '{function_name}'. Supported types: wait, interval, fixed_rate, at, once, onceon, hourly, cron"
+            )),
+        }
+    }
+
+    /// Parse one task per line, skipping blank lines and `#` comments.
+    ///
+    /// Errors report the line number, counting from 1.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use easy_schedule::Task;
+    ///
+    /// let tasks = Task::parse_many("# backups\nat(02:00)\n\ninterval(60)").unwrap();
+    /// assert_eq!(tasks.len(), 2);
+    /// ```
+    pub fn parse_many(s: &str) -> Result<Vec<Self>, String> {
+        s.lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+            .map(|(number, line)| Self::parse(line).map_err(|err| format!("line {number}: {err}")))
+            .collect()
+    }
+
+    /// Parse a named schedule, following the cron shortcuts.
+    ///
+    /// | Alias       | Task                        |
+    /// | ----------- | --------------------------- |
+    /// | `@minutely` | `Interval(60)`              |
+    /// | `@hourly`   | `Hourly(0)`                 |
+    /// | `@daily`    | `At(00:00)`                 |
+    /// | `@weekly`   | `Weekly([1], 00:00)`        |
+    fn parse_alias(alias: &str) -> Result<Self, String> {
+        match alias {
+            "@minutely" => Ok(Task::Interval(60, None)),
+            "@hourly" => Ok(Task::Hourly(0, None)),
+            "@daily" => Ok(Task::At(Time::MIDNIGHT, None)),
+            "@weekly" => Ok(Task::Weekly(vec![1], Time::MIDNIGHT, None)),
             _ => Err(format!(
-                "Unknown task type '{function_name}'. Supported types: wait, interval, at, once"
+                "Unknown schedule alias '{alias}'. Supported aliases: @minutely, @hourly, @daily, @weekly"
             )),
         }
     }
 
+    /// Split the trailing clauses off the primary argument.
+    ///
+    /// ```text
+    /// primary := value { keyword value }
+    /// keyword := "until"
+    /// ```
+    ///
+    /// e.g. `09:00 until 2025-01-01`, each keyword may appear once.
+    fn parse_clauses(primary: &str) -> Result<(String, Vec<Clause>), String> {
+        const KEYWORDS: [&str; 1] = ["until"];
+
+        let mut value = Vec::new();
+        let mut clauses: Vec<(&'static str, Vec<&str>)> = Vec::new();
+        for token in primary.split_whitespace() {
+            if let Some(keyword) = KEYWORDS.iter().find(|keyword| **keyword == token) {
+                if clauses.iter().any(|(seen, _)| seen == keyword) {
+                    return Err(format!("Duplicate '{keyword}' in '{primary}'"));
+                }
+                clauses.push((keyword, Vec::new()));
+            } else if let Some((_, clause)) = clauses.last_mut() {
+                clause.push(token);
+            } else {
+                value.push(token);
+            }
+        }
+
+        let clauses = clauses
+            .into_iter()
+            .map(|(keyword, tokens)| {
+                if tokens.is_empty() {
+                    return Err(format!("Missing value after '{keyword}' in '{primary}'"));
+                }
+                Ok((keyword, tokens.join(" ")))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok((value.join(" "), clauses))
+    }
+
     fn parse_arguments(args: &str) -> Result<(String, Option<Vec<Skip>>), String> {
         let args = args.trim();
 
@@ -289,9 +1619,55 @@ impl Task {
         }
     }
 
+    /// Cron fields contain commas themselves, so skip conditions must follow a comma
+    /// and whitespace or `[`, like `cron(0 9,18 * * *, weekday 6)`.
+    fn parse_cron_arguments(args: &str) -> Result<(String, Option<Vec<Skip>>), String> {
+        let args = args.trim();
+        let separator = args.char_indices().find(|(pos, c)| {
+            *c == ','
+                && args[pos + 1..]
+                    .chars()
+                    .next()
+                    .is_some_and(|next| next.is_whitespace() || next == '[')
+        });
+        match separator {
+            Some((comma_pos, _)) => {
+                let skip_conditions = Self::parse_skip_conditions(&args[comma_pos + 1..])?;
+                Ok((args[..comma_pos].trim().to_string(), Some(skip_conditions)))
+            }
+            None => Ok((args.to_string(), None)),
+        }
+    }
+
+    /// `at` takes several times, like `at(09:00,13:00, weekday 6)`, so skip conditions
+    /// start at the first comma not followed by a time.
+    fn parse_at_arguments(args: &str) -> Result<(String, Option<Vec<Skip>>), String> {
+        let args = args.trim();
+        let separator = args.char_indices().find(|(pos, c)| {
+            *c == ','
+                && !args[pos + 1..]
+                    .trim_start()
+                    .starts_with(|next: char| next.is_ascii_digit())
+        });
+        match separator {
+            Some((comma_pos, _)) => {
+                let skip_conditions = Self::parse_skip_conditions(&args[comma_pos + 1..])?;
+                Ok((args[..comma_pos].trim().to_string(), Some(skip_conditions)))
+            }
+            None => Ok((args.to_string(), None)),
+        }
+    }
+
     fn parse_skip_conditions(skip_str: &str) -> Result<Vec<Skip>, String> {
         let skip_str = skip_str.trim();
 
+        // a top level `any[...]` is a plain list
+        match Self::parse_skip_group(skip_str)? {
+            Some(Skip::Any(any)) => return Ok(any),
+            Some(group) => return Ok(vec![group]),
+            None => {}
+        }
+
         // Check if it's a list format [...]
         if skip_str.starts_with('[') && skip_str.ends_with(']') {
             let list_content = &skip_str[1..skip_str.len() - 1];
@@ -311,11 +1687,32 @@ impl Task {
             return Ok(skips);
         }
 
-        // Split by comma and parse each skip condition
-        for part in list_str.split(',') {
+        // Split by the commas outside of groups and parse each skip condition, a comma
+        // right before a digit continues a list like `weekday 6,7`
+        let mut depth = 0usize;
+        let mut start = 0;
+        let mut parts = Vec::new();
+        for (pos, c) in list_str.char_indices() {
+            match c {
+                '[' | '(' => depth += 1,
+                ']' | ')' => depth = depth.saturating_sub(1),
+                ',' if depth == 0
+                    && !list_str[pos + 1..].starts_with(|next: char| next.is_ascii_digit()) =>
+                {
+                    parts.push(&list_str[start..pos]);
+                    start = pos + 1;
+                }
+                _ => {}
+            }
+        }
+        parts.push(&list_str[start..]);
+        for part in parts {
             let part = part.trim();
             if !part.is_empty() {
-                let skip = Self::parse_single_skip(part)?;
+                let skip = match Self::parse_skip_group(part)? {
+                    Some(group) => group,
+                    None => Self::parse_single_skip(part)?,
+                };
                 skips.push(skip);
             }
         }
@@ -323,7 +1720,35 @@ impl Task {
         Ok(skips)
     }
 
-    fn parse_single_skip(skip_str: &str) -> Result<Skip, String> {
+    /// `all[...]` or `all(...)` skips when every condition matches, `any[...]` or
+    /// `any(...)` when one does. Groups nest, e.g. `any(date 2024-12-25, all(weekday 6,
+    /// time 18:00..23:59))`
+    fn parse_skip_group(skip_str: &str) -> Result<Option<Skip>, String> {
+        for keyword in ["all", "any"] {
+            let Some(list) = skip_str.strip_prefix(keyword) else {
+                continue;
+            };
+            let list = list.trim_start();
+            let inner = list
+                .strip_prefix('[')
+                .and_then(|list| list.strip_suffix(']'))
+                .or_else(|| {
+                    list.strip_prefix('(')
+                        .and_then(|list| list.strip_suffix(')'))
+                });
+            if let Some(inner) = inner {
+                let skips = Self::parse_skip_list(inner)?;
+                return Ok(Some(if keyword == "all" {
+                    Skip::All(skips)
+                } else {
+                    Skip::Any(skips)
+                }));
+            }
+        }
+        Ok(None)
+    }
+
+    pub(crate) fn parse_single_skip(skip_str: &str) -> Result<Skip, String> {
         let skip_str = skip_str.trim();
         let parts: Vec<&str> = skip_str.split_whitespace().collect();
 
@@ -335,47 +1760,69 @@ impl Task {
             "weekday" => {
                 if parts.len() != 2 {
                     return Err(format!(
-                        "Invalid weekday format: '{skip_str}'. Expected 'weekday N'"
+                        "Invalid weekday format: '{skip_str}'. Expected 'weekday N', 'weekday N,M' or 'weekday N-M'"
                     ));
                 }
-                let day = parts[1]
-                    .parse::<u8>()
-                    .map_err(|_| format!("Invalid weekday number: '{}'", parts[1]))?;
-                if !(1..=7).contains(&day) {
-                    return Err(format!("Weekday must be between 1-7, got: {day}"));
+                let parse_day = |day: &str| {
+                    let day = day
+                        .parse::<u8>()
+                        .map_err(|_| format!("Invalid weekday number: '{day}'"))?;
+                    if !(1..=7).contains(&day) {
+                        return Err(format!("Weekday must be between 1-7, got: {day}"));
+                    }
+                    Ok(day)
+                };
+                if let Some((start, end)) = parts[1]
+                    .split_once("..")
+                    .or_else(|| parts[1].split_once('-'))
+                {
+                    let (start, end) = (parse_day(start)?, parse_day(end)?);
+                    return Ok(Skip::DayRange(start as usize, end as usize));
                 }
-                Ok(Skip::Day(vec![day]))
+                let days = parts[1]
+                    .split(',')
+                    .map(parse_day)
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(Skip::Day(days))
             }
-            "date" => {
+            "month" => {
                 if parts.len() != 2 {
                     return Err(format!(
-                        "Invalid date format: '{skip_str}'. Expected 'date YYYY-MM-DD'"
+                        "Invalid month format: '{skip_str}'. Expected 'month N'"
                     ));
                 }
-                let date_str = parts[1];
-                let date_parts: Vec<&str> = date_str.split('-').collect();
-                if date_parts.len() != 3 {
+                let parse_month = |month: &str| {
+                    let month = month
+                        .parse::<u8>()
+                        .map_err(|_| format!("Invalid month number: '{month}'"))?;
+                    if !(1..=12).contains(&month) {
+                        return Err(format!("Month must be between 1-12, got: {month}"));
+                    }
+                    Ok(month)
+                };
+                if let Some((start, end)) = parts[1].split_once("..") {
+                    return Ok(Skip::MonthRange(parse_month(start)?, parse_month(end)?));
+                }
+                let months = parts[1]
+                    .split(',')
+                    .map(parse_month)
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(Skip::Month(months))
+            }
+            "date" => {
+                if parts.len() != 2 {
                     return Err(format!(
-                        "Invalid date format: '{date_str}'. Expected 'YYYY-MM-DD'"
+                        "Invalid date format: '{skip_str}'. Expected 'date YYYY-MM-DD[..YYYY-MM-DD]'"
                     ));
                 }
-
-                let year = date_parts[0]
-                    .parse::<i32>()
-                    .map_err(|_| format!("Invalid year: '{}'", date_parts[0]))?;
-                let month = date_parts[1]
-                    .parse::<u8>()
-                    .map_err(|_| format!("Invalid month: '{}'", date_parts[1]))?;
-                let day = date_parts[2]
-                    .parse::<u8>()
-                    .map_err(|_| format!("Invalid day: '{}'", date_parts[2]))?;
-
-                let month_enum =
-                    time::Month::try_from(month).map_err(|_| format!("Invalid month: {month}"))?;
-                let date = time::Date::from_calendar_date(year, month_enum, day)
-                    .map_err(|_| format!("Invalid date: {year}-{month}-{day}"))?;
-
-                Ok(Skip::Date(date))
+                let date_range = parts[1];
+                if let Some((start_str, end_str)) = date_range.split_once("..") {
+                    let start = Self::parse_date(start_str)?;
+                    let end = Self::parse_date(end_str)?;
+                    Skip::date_range(start, end)
+                } else {
+                    Ok(Skip::Date(Self::parse_date(date_range)?))
+                }
             }
             "time" => {
                 if parts.len() != 2 {
@@ -403,12 +1850,163 @@ impl Task {
                     Ok(Skip::Time(time))
                 }
             }
+            "only_weekday" => {
+                if parts.len() != 2 {
+                    return Err(format!(
+                        "Invalid only_weekday format: '{skip_str}'. Expected 'only_weekday N'"
+                    ));
+                }
+                match Self::parse_single_skip(&format!("weekday {}", parts[1]))? {
+                    Skip::DayRange(start, end) => {
+                        let (start, end) = (start as u8, end as u8);
+                        let days = if start <= end {
+                            (start..=end).collect()
+                        } else {
+                            (start..=7).chain(1..=end).collect()
+                        };
+                        Ok(Skip::OnlyDay(days))
+                    }
+                    Skip::Day(days) => Ok(Skip::OnlyDay(days)),
+                    _ => Err(format!("Invalid only_weekday format: '{skip_str}'")),
+                }
+            }
+            "only_time" => {
+                let range = parts
+                    .get(1)
+                    .filter(|_| parts.len() == 2)
+                    .map(|range| Self::parse_single_skip(&format!("time {range}")))
+                    .transpose()?;
+                match range {
+                    Some(Skip::TimeRange(start, end)) => Ok(Skip::OnlyTimeRange(start, end)),
+                    _ => Err(format!(
+                        "Invalid only_time format: '{skip_str}'. Expected 'only_time HH:MM..HH:MM'"
+                    )),
+                }
+            }
+            "firstofmonth" | "lastofmonth" => {
+                if parts.len() != 1 {
+                    return Err(format!(
+                        "Invalid {} format: '{skip_str}'. Expected no arguments",
+                        parts[0]
+                    ));
+                }
+                if parts[0] == "firstofmonth" {
+                    Ok(Skip::FirstOfMonth)
+                } else {
+                    Ok(Skip::LastOfMonth)
+                }
+            }
             _ => Err(format!(
-                "Unknown skip type: '{}'. Supported types: weekday, date, time",
+                "Unknown skip type: '{}'. Supported types: weekday, month, date, time, only_weekday, only_time, firstofmonth, lastofmonth",
                 parts[0]
             )),
         }
     }
+
+    /// a time of the day as `HH:MM:SS` or `HH:MM`
+    fn parse_time_of_day(time: &str) -> Option<Time> {
+        let with_seconds = format_description!("[hour]:[minute]:[second]");
+        let format = format_description!("[hour]:[minute]");
+        Time::parse(time, &with_seconds)
+            .or_else(|_| Time::parse(time, &format))
+            .ok()
+    }
+
+    fn parse_date(date_str: &str) -> Result<Date, String> {
+        let date_parts: Vec<&str> = date_str.split('-').collect();
+        if date_parts.len() != 3 {
+            return Err(format!(
+                "Invalid date format: '{date_str}'. Expected 'YYYY-MM-DD'"
+            ));
+        }
+
+        let year = date_parts[0]
+            .parse::<i32>()
+            .map_err(|_| format!("Invalid year: '{}'", date_parts[0]))?;
+        let month = date_parts[1]
+            .parse::<u8>()
+            .map_err(|_| format!("Invalid month: '{}'", date_parts[1]))?;
+        let day = date_parts[2]
+            .parse::<u8>()
+            .map_err(|_| format!("Invalid day: '{}'", date_parts[2]))?;
+
+        let month_enum =
+            time::Month::try_from(month).map_err(|_| format!("Invalid month: {month}"))?;
+        time::Date::from_calendar_date(year, month_enum, day)
+            .map_err(|_| format!("Invalid date: {year}-{month}-{day}"))
+    }
+
+    /// parse `10`, `10s`, `250ms`, `1.5s` or a sum of `d`, `h`, `m`, `s` and `ms` parts
+    /// like `1h30m`
+    ///
+    /// whole seconds stay seconds, anything else is converted to milliseconds
+    fn parse_delay(value: &str) -> Option<Delay> {
+        let value = value.trim();
+        if let Some(millis) = value.strip_suffix("ms")
+            && let Ok(millis) = millis.trim().parse::<u64>()
+        {
+            return Some(Delay::Millis(millis));
+        }
+        let seconds = value.strip_suffix('s').unwrap_or(value).trim();
+        if let Ok(seconds) = seconds.parse::<u64>() {
+            return Some(Delay::Seconds(seconds));
+        }
+        if seconds.contains('.') {
+            return Self::parse_fractional_seconds(seconds);
+        }
+        if value.is_empty() {
+            return None;
+        }
+        let mut millis: u64 = 0;
+        let mut rest = value;
+        while !rest.is_empty() {
+            let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+            let number = rest[..digits].parse::<u64>().ok()?;
+            rest = rest[digits..].trim_start();
+            let (unit, scale) = [
+                ("ms", 1),
+                ("d", 86_400_000),
+                ("h", 3_600_000),
+                ("m", 60_000),
+                ("s", 1000),
+            ]
+            .into_iter()
+            .find(|(unit, _)| rest.starts_with(unit))?;
+            rest = rest[unit.len()..].trim_start();
+            millis = millis.checked_add(number.checked_mul(scale)?)?;
+        }
+        if millis.is_multiple_of(1000) {
+            Some(Delay::Seconds(millis / 1000))
+        } else {
+            Some(Delay::Millis(millis))
+        }
+    }
+
+    /// parse seconds with up to three decimals like `1.5` into milliseconds
+    fn parse_fractional_seconds(seconds: &str) -> Option<Delay> {
+        let (whole, fraction) = seconds.split_once('.')?;
+        if fraction.is_empty()
+            || fraction.len() > 3
+            || !fraction.bytes().all(|b| b.is_ascii_digit())
+        {
+            return None;
+        }
+        let whole = whole.parse::<u64>().ok()?;
+        let fraction = format!("{fraction:0<3}").parse::<u64>().ok()?;
+        whole
+            .checked_mul(1000)?
+            .checked_add(fraction)
+            .map(Delay::Millis)
+    }
+}
+
+impl std::str::FromStr for Task {
+    type Err = String;
+
+    /// Parse a task from a string, see [`Task::parse`].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Task::parse(s)
+    }
 }
 
 impl From<&str> for Task {
@@ -447,9 +2045,9 @@ macro_rules! task {
     (interval $seconds:tt) => {
         $crate::Task::Interval($seconds, None)
     };
-    (at $hour:tt : $minute:tt) => {
+    (at $hour:tt : $minute:tt $(: $second:tt)?) => {
         $crate::Task::At(
-            time::Time::from_hms($hour, $minute, 0).unwrap(),
+            time::Time::from_hms($hour, $minute, $crate::task!(@second $($second)?)).unwrap(),
             None
         )
     };
@@ -485,23 +2083,23 @@ macro_rules! task {
         )]))
     };
 
-    (at $hour:tt : $minute:tt, weekday $day:tt) => {
+    (at $hour:tt : $minute:tt $(: $second:tt)?, weekday $day:tt) => {
         $crate::Task::At(
-            time::Time::from_hms($hour, $minute, 0).unwrap(),
+            time::Time::from_hms($hour, $minute, $crate::task!(@second $($second)?)).unwrap(),
             Some(vec![$crate::Skip::Day(vec![$day])])
         )
     };
-    (at $hour:tt : $minute:tt, date $year:tt - $month:tt - $day:tt) => {
+    (at $hour:tt : $minute:tt $(: $second:tt)?, date $year:tt - $month:tt - $day:tt) => {
         $crate::Task::At(
-            time::Time::from_hms($hour, $minute, 0).unwrap(),
+            time::Time::from_hms($hour, $minute, $crate::task!(@second $($second)?)).unwrap(),
             Some(vec![$crate::Skip::Date(
                 time::Date::from_calendar_date($year, time::Month::try_from($month).unwrap(), $day).unwrap()
             )])
         )
     };
-    (at $hour:tt : $minute:tt, time $start_h:tt : $start_m:tt .. $end_h:tt : $end_m:tt) => {
+    (at $hour:tt : $minute:tt $(: $second:tt)?, time $start_h:tt : $start_m:tt .. $end_h:tt : $end_m:tt) => {
         $crate::Task::At(
-            time::Time::from_hms($hour, $minute, 0).unwrap(),
+            time::Time::from_hms($hour, $minute, $crate::task!(@second $($second)?)).unwrap(),
             Some(vec![$crate::Skip::TimeRange(
                 time::Time::from_hms($start_h, $start_m, 0).unwrap(),
                 time::Time::from_hms($end_h, $end_m, 0).unwrap()
@@ -516,12 +2114,43 @@ macro_rules! task {
     (interval $seconds:tt, [$($skip:tt)*]) => {
         $crate::Task::Interval($seconds, Some($crate::task!(@build_skips $($skip)*)))
     };
-    (at $hour:tt : $minute:tt, [$($skip:tt)*]) => {
+    (at $hour:tt : $minute:tt $(: $second:tt)?, [$($skip:tt)*]) => {
         $crate::Task::At(
-            time::Time::from_hms($hour, $minute, 0).unwrap(),
+            time::Time::from_hms($hour, $minute, $crate::task!(@second $($second)?)).unwrap(),
             Some($crate::task!(@build_skips $($skip)*))
         )
     };
+    (cron $expr:literal, [$($skip:tt)*]) => {
+        $crate::Task::Cron($expr.to_string(), Some($crate::task!(@build_skips $($skip)*)))
+    };
+
+    // 其他单个skip条件，如 daterange 和 dayrange
+    (wait $seconds:tt, $($skip:tt)+) => {
+        $crate::Task::Wait($seconds, Some($crate::task!(@build_skips $($skip)+)))
+    };
+    (interval $seconds:tt, $($skip:tt)+) => {
+        $crate::Task::Interval($seconds, Some($crate::task!(@build_skips $($skip)+)))
+    };
+    (at $hour:tt : $minute:tt $(: $second:tt)?, $($skip:tt)+) => {
+        $crate::Task::At(
+            time::Time::from_hms($hour, $minute, $crate::task!(@second $($second)?)).unwrap(),
+            Some($crate::task!(@build_skips $($skip)+))
+        )
+    };
+    (cron $expr:literal $(, $($skip:tt)+)?) => {
+        $crate::Task::Cron(
+            $expr.to_string(),
+            $crate::task!(@optional_skips $($($skip)+)?)
+        )
+    };
+
+    // 辅助宏：可选的秒
+    (@second) => { 0 };
+    (@second $second:tt) => { $second };
+
+    // 辅助宏：可选的skip列表
+    (@optional_skips) => { None };
+    (@optional_skips $($skip:tt)+) => { Some($crate::task!(@build_skips $($skip)+)) };
 
     // 辅助宏：构建skip列表
     (@build_skips) => { vec![] };
@@ -551,53 +2180,305 @@ macro_rules! task {
             skips
         }
     };
+    (@build_skips daterange $start_y:tt - $start_m:tt - $start_d:tt .. $end_y:tt - $end_m:tt - $end_d:tt $(, $($rest:tt)*)?) => {
+        {
+            let mut skips = vec![$crate::Skip::DateRange(
+                time::Date::from_calendar_date($start_y, time::Month::try_from($start_m).unwrap(), $start_d).unwrap(),
+                time::Date::from_calendar_date($end_y, time::Month::try_from($end_m).unwrap(), $end_d).unwrap()
+            )];
+            $(skips.extend($crate::task!(@build_skips $($rest)*));)?
+            skips
+        }
+    };
+    (@build_skips dayrange $start:tt .. $end:tt $(, $($rest:tt)*)?) => {
+        {
+            let mut skips = vec![$crate::Skip::DayRange($start, $end)];
+            $(skips.extend($crate::task!(@build_skips $($rest)*));)?
+            skips
+        }
+    };
 }
 
+/// the syntax of [`Task::parse`] where there is one, see [`Task::to_config_string`], so
+/// `Task::parse(&task.to_string())` gives the task back. Other tasks get a description
+/// the parser rejects
 impl std::fmt::Display for Task {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(config) = self.to_config_string() {
+            return f.write_str(&config);
+        }
+        let skip = self
+            .skips()
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<String>>()
+            .join(", ");
         match self {
-            Task::Wait(wait, skip) => {
-                let skip = skip
-                    .clone()
-                    .unwrap_or_default()
-                    .into_iter()
-                    .map(|s| s.to_string())
-                    .collect::<Vec<String>>()
-                    .join(", ");
-                write!(f, "wait: {wait} {skip}")
+            Task::Wait(wait, _) => write!(f, "wait: {wait} {skip}"),
+            Task::Interval(interval, _) => write!(f, "interval: {interval} {skip}"),
+            Task::WaitMillis(wait, _) => write!(f, "wait: {wait}ms {skip}"),
+            Task::IntervalMillis(interval, _) => write!(f, "interval: {interval}ms {skip}"),
+            Task::FixedRate(interval, _) => write!(f, "fixed rate: {interval} {skip}"),
+            Task::IntervalPhased(interval, anchor, _) => {
+                write!(f, "interval: {interval} from {anchor} {skip}")
             }
-            Task::Interval(interval, skip) => {
-                let skip = skip
-                    .clone()
-                    .unwrap_or_default()
-                    .into_iter()
-                    .map(|s| s.to_string())
-                    .collect::<Vec<String>>()
+            Task::At(time, _) => write!(f, "at: {time} {skip}"),
+            Task::AtUntil(time, until, _) => write!(f, "at: {time} until {until} {skip}"),
+            Task::AtMulti(times, _) => {
+                let times = times
+                    .iter()
+                    .map(|time| time.to_string())
+                    .collect::<Vec<_>>()
                     .join(", ");
-                write!(f, "interval: {interval} {skip}")
+                write!(f, "at: {times} {skip}")
             }
-            Task::At(time, skip) => {
-                let skip = skip
-                    .clone()
-                    .unwrap_or_default()
-                    .into_iter()
-                    .map(|s| s.to_string())
-                    .collect::<Vec<String>>()
-                    .join(", ");
-                write!(f, "at: {time} {skip}")
-            }
-            Task::Once(time, skip) => {
-                let skip = skip
-                    .clone()
-                    .unwrap_or_default()
-                    .into_iter()
-                    .map(|s| s.to_string())
-                    .collect::<Vec<String>>()
-                    .join(", ");
-                write!(f, "once: {time} {skip}")
+            Task::Once(time, _) => write!(f, "once: {time} {skip}"),
+            Task::OnceOn(date, time, _) => write!(f, "once on: {date} at {time} {skip}"),
+            Task::Hourly(minute, _) => write!(f, "hourly: {minute} {skip}"),
+            Task::Weekly(days, time, _) => write!(f, "weekly: {days:?} {time} {skip}"),
+            Task::BusinessDayInterval(n, time, _) => {
+                write!(f, "business days: {n} {time} {skip}")
+            }
+            Task::Cron(expr, _) => write!(f, "cron: {expr} {skip}"),
+            Task::AtComputed(..) => write!(f, "at: computed {skip}"),
+            Task::Within(task, start, end) => write!(f, "{task} within {start} - {end}"),
+        }
+    }
+}
+
+/// `time` moved on by `delay`, `None` past the representable range
+fn add_delay(time: OffsetDateTime, delay: Duration) -> Option<OffsetDateTime> {
+    time.checked_add(time::Duration::try_from(delay).ok()?)
+}
+
+/// the first time at or after `from` falling on one of `days`, keeping the time of day
+fn next_weekday(from: OffsetDateTime, days: &[u8]) -> Option<OffsetDateTime> {
+    (0..7)
+        .map(|offset| from + time::Duration::days(offset))
+        .find(|time| days.contains(&time.weekday().number_from_monday()))
+}
+
+impl Task {
+    /// the task in the syntax of [`Task::parse`], e.g. `wait(10, [weekday 6,7, time 12:00])`
+    ///
+    /// [`Task::parse`] reads the string back into the same task, except that an empty
+    /// skip list comes back as `None`
+    ///
+    /// `None` for what the parser has no syntax for: the tasks `IntervalPhased`, `Weekly`,
+    /// `BusinessDayInterval`, `AtComputed` and `Within`, and the skip rules
+    /// `TimeRangeExclusive`, `TimeGrid`, `NthWeekday` and `Not`. Also `None` when times
+    /// are not whole minutes in skips or whole seconds elsewhere, or a `once` offset is
+    /// not whole minutes
+    pub fn to_config_string(&self) -> Option<String> {
+        let primary = match self {
+            Task::Wait(wait, _) => format!("wait({wait}"),
+            Task::WaitMillis(wait, _) => format!("wait({wait}ms"),
+            Task::Interval(interval, _) => format!("interval({interval}"),
+            Task::IntervalMillis(interval, _) => format!("interval({interval}ms"),
+            Task::FixedRate(interval, _) => format!("fixed_rate({interval}"),
+            Task::At(time, _) => format!("at({}", config_time(*time)?),
+            Task::AtUntil(time, until, _) => {
+                format!("at({} until {until}", config_time(*time)?)
+            }
+            Task::AtMulti(times, _) => {
+                let times = times
+                    .iter()
+                    .map(|time| config_time(*time))
+                    .collect::<Option<Vec<_>>>()?;
+                format!("at({}", times.join(","))
+            }
+            Task::Once(at, _) => {
+                let format = format_description!(
+                    "[year]-[month]-[day] [hour]:[minute]:[second] [offset_hour sign:mandatory]"
+                );
+                let format_minutes = format_description!(
+                    "[year]-[month]-[day] [hour]:[minute]:[second] [offset_hour sign:mandatory]:[offset_minute]"
+                );
+                if at.nanosecond() != 0 || at.offset().seconds_past_minute() != 0 {
+                    return None;
+                }
+                let at = if at.offset().minutes_past_hour() == 0 {
+                    at.format(&format)
+                } else {
+                    at.format(&format_minutes)
+                };
+                format!("once({}", at.ok()?)
+            }
+            Task::OnceOn(date, time, _) => format!("onceon({date} at {}", config_time(*time)?),
+            Task::Hourly(minute, _) => format!("hourly({minute}"),
+            Task::Cron(expr, _) => format!("cron({expr}"),
+            Task::IntervalPhased(..)
+            | Task::Weekly(..)
+            | Task::BusinessDayInterval(..)
+            | Task::AtComputed(..)
+            | Task::Within(..) => return None,
+        };
+        let skips = match self.skips() {
+            [] => String::new(),
+            // a single `any` group is read back as a plain list, so keep it in one
+            [Skip::Any(_)] => format!(", [{}]", config_skips(self.skips())?.join(", ")),
+            skips => match config_skips(skips)?.as_slice() {
+                [skip] => format!(", {skip}"),
+                skips => format!(", [{}]", skips.join(", ")),
+            },
+        };
+        Some(format!("{primary}{skips})"))
+    }
+}
+
+/// a time of the day as `HH:MM:SS`, `None` with a fraction of a second
+fn config_time(time: Time) -> Option<String> {
+    (time.nanosecond() == 0).then(|| {
+        format!(
+            "{:02}:{:02}:{:02}",
+            time.hour(),
+            time.minute(),
+            time.second()
+        )
+    })
+}
+
+/// the skip rules in the syntax of [`Task::parse`], one entry per rule
+fn config_skips(skips: &[Skip]) -> Option<Vec<String>> {
+    // skips take whole minutes
+    let minute = |time: Time| {
+        (time.second() == 0 && time.nanosecond() == 0)
+            .then(|| format!("{:02}:{:02}", time.hour(), time.minute()))
+    };
+    let weekday = |day: usize| (1..=7).contains(&day).then_some(day);
+    let month = |month: u8| (1..=12).contains(&month).then_some(month);
+    let mut entries = Vec::with_capacity(skips.len());
+    for skip in skips {
+        match skip {
+            Skip::Date(date) => entries.push(format!("date {date}")),
+            Skip::DateRange(start, end) if start <= end => {
+                entries.push(format!("date {start}..{end}"));
+            }
+            Skip::Day(days) if !days.is_empty() => {
+                let days = days
+                    .iter()
+                    .map(|day| weekday(*day as usize).map(|day| day.to_string()))
+                    .collect::<Option<Vec<_>>>()?;
+                entries.push(format!("weekday {}", days.join(",")));
+            }
+            Skip::DayRange(start, end) => {
+                entries.push(format!("weekday {}..{}", weekday(*start)?, weekday(*end)?));
+            }
+            Skip::Time(time) => entries.push(format!("time {}", minute(*time)?)),
+            Skip::TimeRange(start, end) => {
+                entries.push(format!("time {}..{}", minute(*start)?, minute(*end)?));
+            }
+            Skip::OnlyTimeRange(start, end) => {
+                entries.push(format!("only_time {}..{}", minute(*start)?, minute(*end)?));
             }
+            Skip::OnlyDay(days) => {
+                let (first, last) = (*days.first()?, *days.last()?);
+                // only a run of days following each other, wrapping over the week
+                let run = days.windows(2).all(|pair| pair[1] == pair[0] % 7 + 1);
+                if !run || days.len() > 7 {
+                    return None;
+                }
+                entries.push(if first == last {
+                    format!("only_weekday {}", weekday(first as usize)?)
+                } else {
+                    format!(
+                        "only_weekday {}..{}",
+                        weekday(first as usize)?,
+                        weekday(last as usize)?
+                    )
+                });
+            }
+            Skip::Month(months) if !months.is_empty() => {
+                let months = months
+                    .iter()
+                    .map(|m| month(*m).map(|m| m.to_string()))
+                    .collect::<Option<Vec<_>>>()?;
+                entries.push(format!("month {}", months.join(",")));
+            }
+            Skip::MonthRange(start, end) => {
+                entries.push(format!("month {}..{}", month(*start)?, month(*end)?));
+            }
+            Skip::FirstOfMonth => entries.push("firstofmonth".to_string()),
+            Skip::LastOfMonth => entries.push("lastofmonth".to_string()),
+            Skip::All(all) => entries.push(format!("all[{}]", config_skips(all)?.join(", "))),
+            Skip::Any(any) => entries.push(format!("any[{}]", config_skips(any)?.join(", "))),
+            Skip::DateRange(..)
+            | Skip::Day(_)
+            | Skip::Month(_)
+            | Skip::TimeRangeExclusive(..)
+            | Skip::TimeGrid(..)
+            | Skip::NthWeekday(..)
+            | Skip::Not(_)
+            | Skip::None => return None,
         }
     }
+    Some(entries)
+}
+
+/// the time computed for `date`, in the timezone `offset`
+fn computed_time(compute: &DailyTime, date: Date, offset: UtcOffset) -> OffsetDateTime {
+    let time = compute(date, offset.whole_minutes());
+    date.with_time(time).assume_offset(offset)
+}
+
+/// a `keyword value` clause of a primary argument, see `Task::parse_clauses`
+type Clause = (&'static str, String);
+
+/// a parsed `wait` or `interval` argument, see `Task::parse_delay`
+enum Delay {
+    Seconds(u64),
+    Millis(u64),
+}
+
+/// upper bound of runs stepped over by [`collides_within`]
+const MAX_COLLISION_STEPS: usize = 100_000;
+
+/// the first instant within `window` from now at which both `a` and `b` fire, skips
+/// applied, or `None` if they never fire together in it
+///
+/// a window past the representable range is clamped to it. At most 100000 runs are
+/// compared, and a schedule that stops advancing, like `Interval(0)`, ends after its
+/// first run
+///
+/// `offset` is the timezone offset in minutes, as in [`Scheduler::with_timezone_minutes`](crate::Scheduler::with_timezone_minutes)
+pub fn collides_within(
+    a: &Task,
+    b: &Task,
+    window: Duration,
+    offset: i16,
+) -> Option<OffsetDateTime> {
+    let now = get_now(offset).unwrap_or_else(|_| OffsetDateTime::now_utc());
+    let until = time::Duration::try_from(window)
+        .ok()
+        .and_then(|window| now.checked_add(window))
+        .unwrap_or_else(|| time::PrimitiveDateTime::MAX.assume_utc());
+    fn runs(
+        task: &Task,
+        now: OffsetDateTime,
+        until: OffsetDateTime,
+    ) -> impl Iterator<Item = OffsetDateTime> + '_ {
+        let mut last: Option<OffsetDateTime> = None;
+        task.run_times(now)
+            .take_while(move |time| {
+                let advanced = last.is_none_or(|last| *time > last);
+                last = Some(*time);
+                advanced && *time <= until
+            })
+            // skips are read in the given offset, whatever the offset of the run
+            .filter(move |time| !task.is_skipped(time.to_offset(now.offset())))
+    }
+    let mut a = runs(a, now, until).peekable();
+    let mut b = runs(b, now, until).peekable();
+    // both are in order, so step the earlier one until they meet
+    for _ in 0..MAX_COLLISION_STEPS {
+        let (next_a, next_b) = (*a.peek()?, *b.peek()?);
+        match next_a.cmp(&next_b) {
+            std::cmp::Ordering::Less => a.next(),
+            std::cmp::Ordering::Greater => b.next(),
+            std::cmp::Ordering::Equal => return Some(next_a),
+        };
+    }
+    None
 }
 
 pub fn get_next_time(now: OffsetDateTime, time: Time) -> OffsetDateTime {
@@ -609,8 +2490,6 @@ pub fn get_next_time(now: OffsetDateTime, time: Time) -> OffsetDateTime {
 }
 
 pub fn get_now(timezone_minutes: i16) -> Result<OffsetDateTime, time::error::ComponentRange> {
-    let hours = timezone_minutes / 60;
-    let minutes = timezone_minutes % 60;
-    let offset = UtcOffset::from_hms(hours as i8, minutes as i8, 0)?;
+    let offset = UtcOffset::from_whole_seconds(timezone_minutes as i32 * 60)?;
     Ok(OffsetDateTime::now_utc().to_offset(offset))
 }
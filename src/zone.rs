@@ -0,0 +1,59 @@
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime, Offset, TimeZone};
+use chrono_tz::Tz;
+use time::{OffsetDateTime, UtcOffset};
+
+/// parse an IANA timezone name like `Europe/London`
+pub(crate) fn parse(name: &str) -> Result<Tz, String> {
+    name.parse::<Tz>().map_err(|_| {
+        format!("Unknown timezone '{name}'. Expected an IANA name like 'Europe/London'")
+    })
+}
+
+/// the offset of `tz` at the instant `at`
+pub(crate) fn offset_at(tz: Tz, at: OffsetDateTime) -> UtcOffset {
+    let utc = at.to_offset(UtcOffset::UTC);
+    let offset = tz.offset_from_utc_datetime(&naive(utc)).fix();
+    UtcOffset::from_whole_seconds(offset.local_minus_utc()).unwrap_or(UtcOffset::UTC)
+}
+
+/// the current time in `tz`
+pub(crate) fn now(tz: Tz) -> OffsetDateTime {
+    let now = OffsetDateTime::now_utc();
+    now.to_offset(offset_at(tz, now))
+}
+
+/// the instant `tz` shows the date and time of `at`, whatever its offset
+///
+/// a time skipped by daylight saving moves forward by the gap, a time repeated by it
+/// resolves to the first of the two
+pub(crate) fn localize(tz: Tz, at: OffsetDateTime) -> OffsetDateTime {
+    let local = naive(at);
+    let offset = match tz.offset_from_local_datetime(&local).earliest() {
+        Some(offset) => offset.fix().local_minus_utc(),
+        None => {
+            // in a gap, read the time with the offset from before it
+            let before = tz
+                .offset_from_utc_datetime(&(local - chrono::Duration::days(1)))
+                .fix();
+            let instant = at.replace_offset(
+                UtcOffset::from_whole_seconds(before.local_minus_utc()).unwrap_or(UtcOffset::UTC),
+            );
+            return instant.to_offset(offset_at(tz, instant));
+        }
+    };
+    at.replace_offset(UtcOffset::from_whole_seconds(offset).unwrap_or(UtcOffset::UTC))
+}
+
+/// the date and time of `at`, dropping the offset
+fn naive(at: OffsetDateTime) -> NaiveDateTime {
+    let date = NaiveDate::from_ymd_opt(at.year(), at.month() as u32, at.day() as u32);
+    let time = NaiveTime::from_hms_nano_opt(
+        at.hour() as u32,
+        at.minute() as u32,
+        at.second() as u32,
+        at.nanosecond(),
+    );
+    date.zip(time)
+        .map(|(date, time)| date.and_time(time))
+        .unwrap_or_default()
+}
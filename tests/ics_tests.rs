@@ -0,0 +1,59 @@
+use easy_schedule::prelude::*;
+use std::time::Duration;
+use time::{OffsetDateTime, UtcOffset, macros::format_description, macros::time};
+
+#[derive(Debug)]
+struct Report(Task);
+
+#[async_trait]
+impl Notifiable for Report {
+    fn get_task(&self) -> Task {
+        self.0.clone()
+    }
+
+    async fn on_time(&self, _cancel: CancellationToken) {}
+}
+
+fn ics_timestamp(time: OffsetDateTime) -> String {
+    let format = format_description!("[year][month][day]T[hour][minute][second]Z");
+    time.to_offset(UtcOffset::UTC).format(&format).unwrap()
+}
+
+#[tokio::test]
+async fn test_ics_daily_at_task() {
+    let scheduler = Scheduler::new();
+    let handle = scheduler.run(Report(Task::At(time!(09:00), None))).await;
+    let next = handle.next_run().unwrap();
+
+    let ics = scheduler.to_ics(Duration::from_secs(7 * 24 * 3600));
+
+    assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+    assert!(ics.ends_with("END:VCALENDAR\r\n"));
+    assert!(ics.contains("BEGIN:VEVENT\r\n"));
+    assert!(ics.contains(&format!("DTSTART:{}\r\n", ics_timestamp(next))));
+    assert!(ics.contains("RRULE:FREQ=DAILY;UNTIL="));
+    assert!(ics.contains("SUMMARY:Report\r\n"));
+
+    scheduler.stop();
+}
+
+#[tokio::test]
+async fn test_ics_enumerates_skipped_schedules() {
+    let scheduler = Scheduler::new();
+    let skip = Some(vec![Skip::Day(vec![6, 7])]);
+    scheduler.run(Report(Task::At(time!(09:00), skip))).await;
+
+    let ics = scheduler.to_ics(Duration::from_secs(7 * 24 * 3600));
+
+    assert!(!ics.contains("RRULE"));
+    assert_eq!(ics.matches("BEGIN:VEVENT").count(), 5);
+
+    scheduler.stop();
+    assert_eq!(
+        scheduler
+            .to_ics(Duration::from_secs(3600))
+            .matches("VEVENT")
+            .count(),
+        0
+    );
+}
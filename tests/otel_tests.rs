@@ -0,0 +1,64 @@
+#![cfg(feature = "otel")]
+
+use async_trait::async_trait;
+use easy_schedule::prelude::*;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+
+#[derive(Debug)]
+struct Ping;
+
+#[async_trait]
+impl Notifiable for Ping {
+    fn get_task(&self) -> Task {
+        Task::Interval(1, None)
+    }
+
+    async fn on_time(&self, _cancel: CancellationToken) {
+        tracing::info!("ping");
+    }
+}
+
+/// records the `task.name` field and the parent of every `fire` span
+#[derive(Clone, Default)]
+struct FireSpans(Arc<Mutex<Vec<(String, bool)>>>);
+
+struct TaskName(String);
+
+impl Visit for TaskName {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "task.name" {
+            self.0 = format!("{value:?}");
+        }
+    }
+}
+
+impl<S: tracing::Subscriber> Layer<S> for FireSpans {
+    fn on_new_span(&self, attrs: &Attributes<'_>, _id: &Id, _ctx: Context<'_, S>) {
+        if attrs.metadata().name() != "fire" {
+            return;
+        }
+        let mut name = TaskName(String::new());
+        attrs.record(&mut name);
+        self.0.lock().unwrap().push((name.0, attrs.is_root()));
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_span_per_fire() {
+    let spans = FireSpans::default();
+    let subscriber = tracing_subscriber::registry().with(spans.clone());
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let scheduler = Scheduler::new();
+    scheduler.run(Ping).await;
+    tokio::time::sleep(Duration::from_millis(3500)).await;
+    scheduler.stop();
+
+    let spans = spans.0.lock().unwrap();
+    assert_eq!(spans.len(), 3);
+    assert!(spans.iter().all(|(name, root)| name == "Ping" && *root));
+}
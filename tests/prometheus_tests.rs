@@ -0,0 +1,45 @@
+#![cfg(feature = "prometheus")]
+
+use async_trait::async_trait;
+use easy_schedule::prelude::*;
+use prometheus::{Encoder, TextEncoder};
+use std::time::Duration;
+
+#[derive(Debug)]
+struct Poll(Task);
+
+#[async_trait]
+impl Notifiable for Poll {
+    fn get_task(&self) -> Task {
+        self.0.clone()
+    }
+
+    async fn on_time(&self, _cancel: CancellationToken) {}
+}
+
+fn scrape(scheduler: &Scheduler) -> String {
+    let mut buffer = Vec::new();
+    TextEncoder::new()
+        .encode(&scheduler.metrics_registry().gather(), &mut buffer)
+        .unwrap();
+    String::from_utf8(buffer).unwrap()
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_metrics() {
+    let scheduler = Scheduler::new();
+    scheduler.run(Poll(Task::Interval(1, None))).await;
+    let skip = Some(vec![Skip::Day(vec![1, 2, 3, 4, 5, 6, 7])]);
+    scheduler.run(Poll(Task::Wait(1, skip))).await;
+    tokio::time::sleep(Duration::from_millis(3500)).await;
+
+    let output = scrape(&scheduler);
+    assert!(output.contains("easy_schedule_executions_total{task=\"Poll\"} 3"));
+    assert!(output.contains("easy_schedule_skips_total{task=\"Poll\"} 1"));
+    // the wait task is done, the interval task is still running
+    assert!(output.contains("easy_schedule_active_tasks{task=\"Poll\"} 1"));
+
+    scheduler.stop();
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    assert!(scrape(&scheduler).contains("easy_schedule_active_tasks{task=\"Poll\"} 0"));
+}
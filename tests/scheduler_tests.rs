@@ -3,7 +3,10 @@ use easy_schedule::prelude::*;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::time::Duration;
-use time::{OffsetDateTime, macros::offset};
+use time::{
+    OffsetDateTime,
+    macros::{date, datetime, offset},
+};
 use tokio_util::sync::CancellationToken;
 
 #[derive(Debug, Clone)]
@@ -78,6 +81,87 @@ async fn test_scheduler_creation() {
     assert!(!scheduler_with_minutes.get_cancel().is_cancelled());
 }
 
+#[test]
+fn test_simulate() {
+    let scheduler = Scheduler::new();
+    let weekend = Skip::Day(vec![6, 7]);
+    let task = TestTask::new(Task::At(
+        time::macros::time!(12:00),
+        Some(vec![weekend.clone()]),
+    ));
+    let now = OffsetDateTime::now_utc().to_offset(offset!(+8));
+    let simulated = scheduler.simulate(task.clone(), now + time::Duration::days(7));
+
+    assert_eq!(simulated.len(), 7);
+    for (at, outcome) in &simulated {
+        assert_eq!(at.time(), time::macros::time!(12:00));
+        let expected = match at.weekday() {
+            time::Weekday::Saturday | time::Weekday::Sunday => FireOutcome::Skip(weekend.clone()),
+            _ => FireOutcome::Fire,
+        };
+        assert_eq!(*outcome, expected, "{at}");
+    }
+    let fires = simulated
+        .iter()
+        .filter(|(_, outcome)| *outcome == FireOutcome::Fire);
+    assert_eq!(fires.count(), 5);
+    // nothing ran
+    assert_eq!(task.execution_count(), 0);
+    assert_eq!(scheduler.active_task_count(), 0);
+
+    // dense schedules are capped, schedules that do not advance end
+    let day = now + time::Duration::days(1);
+    let dense = TestTask::new(Task::IntervalMillis(1, None));
+    assert_eq!(scheduler.simulate(dense, day).len(), 10_000);
+    let stuck = TestTask::new(Task::Interval(0, None));
+    assert_eq!(scheduler.simulate(stuck, day).len(), 1);
+}
+
+#[test]
+fn test_with_local_offset() {
+    // reading the offset is refused on Unix while other threads run, like the test threads
+    let scheduler = match Scheduler::with_local_offset() {
+        Ok(scheduler) => scheduler,
+        Err(err) => {
+            assert!(
+                err.starts_with("Failed to read the local timezone offset"),
+                "{err}"
+            );
+            return;
+        }
+    };
+    let local = time::UtcOffset::current_local_offset().unwrap();
+    assert_eq!(scheduler.offset(), local);
+
+    let task = TestTask::new(Task::At(time::macros::time!(09:00), None));
+    let next = scheduler.get_next_run_time(task).unwrap();
+    assert_eq!(next.offset(), local);
+    assert_eq!(next.time(), time::macros::time!(09:00));
+}
+
+#[tokio::test]
+async fn test_scheduler_offset() {
+    let scheduler = Scheduler::with_timezone_minutes(330);
+    assert_eq!(
+        scheduler.offset(),
+        time::UtcOffset::from_hms(5, 30, 0).unwrap()
+    );
+    assert_eq!(scheduler.offset_minutes(), 330);
+
+    assert_eq!(Scheduler::new().offset(), offset!(+8));
+    assert_eq!(Scheduler::with_timezone(-3, -30).offset(), offset!(-3:30));
+    // out of range falls back to UTC
+    assert_eq!(
+        Scheduler::with_timezone_minutes(30 * 60).offset(),
+        time::UtcOffset::UTC
+    );
+    // 257 hours do not wrap around to one hour
+    assert_eq!(
+        Scheduler::with_timezone_minutes(15420).offset(),
+        time::UtcOffset::UTC
+    );
+}
+
 #[tokio::test]
 async fn test_scheduler_stop() {
     let scheduler = Scheduler::new();
@@ -128,6 +212,22 @@ async fn test_once_task_future() {
     assert_eq!(task.skip_count(), 0);
 }
 
+#[tokio::test(start_paused = true)]
+async fn test_once_task_sub_second() {
+    let scheduler = Scheduler::new();
+    let now = OffsetDateTime::now_utc().to_offset(offset!(+8));
+    let task = TestTask::new(Task::Once(now + time::Duration::milliseconds(1500), None));
+
+    scheduler.run(task.clone()).await;
+
+    // not a second early
+    tokio::time::sleep(Duration::from_millis(1400)).await;
+    assert_eq!(task.execution_count(), 0);
+
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(task.execution_count(), 1);
+}
+
 #[tokio::test]
 async fn test_once_task_past() {
     let scheduler = Scheduler::new();
@@ -225,6 +325,46 @@ async fn test_at_task_skip_logic() {
     assert!(task.skip_count() > 0);
 }
 
+#[tokio::test]
+async fn test_task_handle_introspection() {
+    let scheduler = Scheduler::new();
+    let before = OffsetDateTime::now_utc();
+    let handle = scheduler.run(TestTask::new(Task::Interval(5, None))).await;
+
+    assert_eq!(*handle.task(), Task::Interval(5, None));
+    assert_eq!(handle.name(), "TestTask");
+    assert!(handle.created_at() >= before);
+    assert!(!handle.is_cancelled());
+
+    let next = handle.next_run().unwrap();
+    let expected = handle.created_at() + time::Duration::seconds(5);
+    assert!((next - expected).abs() < time::Duration::seconds(1));
+
+    scheduler.stop();
+    assert!(handle.is_cancelled());
+    assert!(handle.next_run().is_none());
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_at_task_fires_once_per_day() {
+    let scheduler = Scheduler::new();
+    let now = OffsetDateTime::now_utc().to_offset(offset!(+8));
+    let fire = now + time::Duration::seconds(2);
+    let at = fire.time();
+    let task = TestTask::new(Task::At(at, None));
+
+    let handle = scheduler.run(task.clone()).await;
+
+    // virtual time races ahead of the wall clock, so the loop sees the same
+    // at time again right after firing
+    tokio::time::sleep(Duration::from_secs(10)).await;
+
+    assert_eq!(task.execution_count(), 1);
+    let next = handle.next_run().unwrap();
+    assert_eq!(next.time(), at);
+    assert!(next.date() > fire.date());
+}
+
 #[tokio::test]
 async fn test_get_next_run_time_wait() {
     let task = Task::Wait(60, None);
@@ -295,6 +435,115 @@ async fn test_get_next_run_time_once_future() {
     assert_eq!(next_time.unwrap(), future_time);
 }
 
+#[tokio::test]
+async fn test_get_next_run_time_within() {
+    let now = OffsetDateTime::now_utc().to_offset(offset!(+8));
+    let day = time::Duration::days(1);
+    let scheduler = Scheduler::new();
+
+    // before the window
+    let task = Task::Interval(60, None).within(now + day, now + day * 2);
+    let next_time = scheduler.get_next_run_time(TestTask::new(task));
+    assert!(next_time.is_some_and(|next| next > now + day));
+
+    // during the window
+    let task = Task::Interval(60, None).within(now - day, now + day);
+    let next_time = scheduler.get_next_run_time(TestTask::new(task));
+    assert!(next_time.is_some_and(|next| next <= now + day));
+
+    // after the window
+    let task = Task::Interval(60, None).within(now - day * 2, now - day);
+    let next_time = scheduler.get_next_run_time(TestTask::new(task));
+    assert!(next_time.is_none());
+}
+
+#[tokio::test]
+async fn test_next_run_times() {
+    let scheduler = Scheduler::new();
+    let task = Task::At(
+        time::macros::time!(09:00),
+        Some(vec![Skip::Day(vec![6, 7])]),
+    );
+
+    let times = scheduler.next_run_times(TestTask::new(task), 10);
+    assert_eq!(times.len(), 10);
+    assert!(times.windows(2).all(|pair| pair[0].date() < pair[1].date()));
+    for time in times {
+        assert_eq!(time.time(), time::macros::time!(09:00));
+        assert!(time.weekday().number_from_monday() <= 5);
+    }
+
+    let times = scheduler.next_run_times(TestTask::new(Task::Interval(60, None)), 3);
+    assert_eq!(times[2] - times[0], time::Duration::minutes(2));
+
+    let now = OffsetDateTime::now_utc().to_offset(offset!(+8));
+    let once = now + time::Duration::minutes(5);
+    let times = scheduler.next_run_times(TestTask::new(Task::Once(once, None)), 3);
+    assert_eq!(times, vec![once]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_once_skips_in_scheduler_offset() {
+    let scheduler = Scheduler::with_timezone_minutes(8 * 60);
+
+    // 20:00 UTC is 04:00 the next day at +8, where the date skip applies
+    let once = datetime!(2030-01-01 20:00 UTC);
+    let skip_local = Task::Once(once, Some(vec![Skip::Date(date!(2030 - 01 - 02))]));
+    assert!(
+        scheduler
+            .next_run_times(TestTask::new(skip_local), 1)
+            .is_empty()
+    );
+    let skip_utc = Task::Once(once, Some(vec![Skip::Date(date!(2030 - 01 - 01))]));
+    assert_eq!(
+        scheduler.next_run_times(TestTask::new(skip_utc), 1),
+        vec![once]
+    );
+
+    // same when it fires
+    let once = OffsetDateTime::now_utc() + time::Duration::seconds(2);
+    let local = once.to_offset(offset!(+8));
+    let task = TestTask::new(Task::Once(once, Some(vec![Skip::Date(local.date())])));
+    scheduler.run(task.clone()).await;
+    tokio::time::sleep(Duration::from_secs(3)).await;
+    assert_eq!(task.execution_count(), 0);
+    assert_eq!(task.skip_count(), 1);
+
+    // the reported next run and the ICS export follow the same offset
+    let scheduler = Scheduler::with_timezone_minutes(8 * 60);
+    let once = datetime!(2030-01-01 20:00 UTC);
+    let window = Duration::from_secs(10 * 366 * 24 * 3600);
+    let kept = scheduler
+        .run(TestTask::new(Task::Once(
+            once,
+            Some(vec![Skip::Date(date!(2030 - 01 - 01))]),
+        )))
+        .await;
+    tokio::time::sleep(Duration::from_millis(1)).await;
+    assert_eq!(kept.next_run(), Some(once));
+    assert!(
+        scheduler
+            .to_ics(window)
+            .contains("DTSTART:20300101T200000Z")
+    );
+    kept.cancel();
+
+    let skipped = scheduler
+        .run(TestTask::new(Task::Once(
+            once,
+            Some(vec![Skip::Date(date!(2030 - 01 - 02))]),
+        )))
+        .await;
+    tokio::time::sleep(Duration::from_millis(1)).await;
+    assert_eq!(skipped.next_run(), None);
+    assert!(
+        !scheduler
+            .to_ics(window)
+            .contains("DTSTART:20300101T200000Z")
+    );
+    skipped.cancel();
+}
+
 #[tokio::test]
 async fn test_get_next_run_time_once_past() {
     let now = OffsetDateTime::now_utc().to_offset(time::macros::offset!(+8));
@@ -332,3 +581,1915 @@ async fn test_get_next_run_time_once_with_skip() {
     // Should return None because the once task is skipped
     assert!(next_time.is_none());
 }
+
+#[derive(Debug, Clone)]
+struct FlakyTask {
+    failures: u32,
+    calls: Arc<std::sync::Mutex<Vec<tokio::time::Instant>>>,
+    opened: Arc<AtomicU32>,
+    closed: Arc<AtomicU32>,
+}
+
+#[async_trait]
+impl Notifiable for FlakyTask {
+    fn get_task(&self) -> Task {
+        Task::Interval(1, None)
+    }
+
+    async fn on_time_fallible(&self, _cancel: CancellationToken) -> Result<(), TaskError> {
+        let mut calls = self.calls.lock().unwrap();
+        calls.push(tokio::time::Instant::now());
+        if calls.len() as u32 <= self.failures {
+            return Err("service down".into());
+        }
+        Ok(())
+    }
+
+    fn circuit_breaker(&self) -> Option<CircuitBreaker> {
+        Some(CircuitBreaker::new(3, Duration::from_secs(10)))
+    }
+
+    async fn on_circuit_open(&self, _cancel: CancellationToken) {
+        self.opened.fetch_add(1, Ordering::SeqCst);
+    }
+
+    async fn on_circuit_close(&self, _cancel: CancellationToken) {
+        self.closed.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_interval_circuit_breaker() {
+    let scheduler = Scheduler::new();
+    let task = FlakyTask {
+        failures: 3,
+        calls: Arc::new(std::sync::Mutex::new(Vec::new())),
+        opened: Arc::new(AtomicU32::new(0)),
+        closed: Arc::new(AtomicU32::new(0)),
+    };
+    let start = tokio::time::Instant::now();
+
+    scheduler.run(task.clone()).await;
+
+    tokio::time::sleep(Duration::from_millis(3500)).await;
+    assert_eq!(task.calls.lock().unwrap().len(), 3);
+    assert_eq!(task.opened.load(Ordering::SeqCst), 1);
+    assert_eq!(task.closed.load(Ordering::SeqCst), 0);
+
+    // paused during the cooldown
+    tokio::time::sleep(Duration::from_secs(9)).await;
+    assert_eq!(task.calls.lock().unwrap().len(), 3);
+
+    tokio::time::sleep(Duration::from_secs(3)).await;
+    let calls = task.calls.lock().unwrap().clone();
+    assert_eq!(task.closed.load(Ordering::SeqCst), 1);
+    assert_eq!(calls.len(), 6);
+    assert_eq!(calls[3] - start, Duration::from_secs(13));
+    assert_eq!(calls[4] - start, Duration::from_secs(14));
+    assert_eq!(task.opened.load(Ordering::SeqCst), 1);
+
+    scheduler.stop();
+}
+
+#[derive(Debug, Clone, Default)]
+struct FirstSuccessTask {
+    calls: Arc<std::sync::Mutex<Vec<tokio::time::Instant>>>,
+}
+
+#[async_trait]
+impl Notifiable for FirstSuccessTask {
+    fn get_task(&self) -> Task {
+        Task::Interval(60, None)
+    }
+
+    async fn on_time_fallible(&self, _cancel: CancellationToken) -> Result<(), TaskError> {
+        let mut calls = self.calls.lock().unwrap();
+        calls.push(tokio::time::Instant::now());
+        if calls.len() == 1 {
+            return Err("not ready".into());
+        }
+        Ok(())
+    }
+
+    fn retry_until_first_success(&self) -> Option<Duration> {
+        Some(Duration::from_secs(5))
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_interval_anchors_to_first_success() {
+    let scheduler = Scheduler::new();
+    let task = FirstSuccessTask::default();
+    let start = tokio::time::Instant::now();
+    scheduler.run(task.clone()).await;
+
+    tokio::time::sleep(Duration::from_secs(200)).await;
+    scheduler.stop();
+
+    // fails at 5s, succeeds on the retry at 10s, then every 60s from there
+    let offsets: Vec<_> = task
+        .calls
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|call| call.duration_since(start).as_secs())
+        .collect();
+    assert_eq!(offsets, vec![5, 10, 70, 130, 190]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_chaos_skip_all() {
+    let scheduler = Scheduler::builder().chaos_skip_probability(1.0).build();
+    let task = TestTask::new(Task::Interval(1, None));
+
+    scheduler.run(task.clone()).await;
+    tokio::time::sleep(Duration::from_millis(5500)).await;
+
+    assert_eq!(task.execution_count(), 0);
+    assert_eq!(task.skip_count(), 5);
+    scheduler.stop();
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_gate_skips_all_tasks() {
+    let gate = Arc::new(AtomicBool::new(false));
+    let scheduler = Scheduler::builder().gate(gate.clone()).build();
+    let interval = TestTask::new(Task::Interval(1, None));
+    let slow = TestTask::new(Task::Interval(2, None));
+
+    scheduler.run(interval.clone()).await;
+    scheduler.run(slow.clone()).await;
+    tokio::time::sleep(Duration::from_millis(4500)).await;
+
+    assert_eq!(interval.execution_count(), 0);
+    assert_eq!(interval.skip_count(), 4);
+    assert_eq!(slow.execution_count(), 0);
+    assert_eq!(slow.skip_count(), 2);
+
+    gate.store(true, Ordering::Relaxed);
+    tokio::time::sleep(Duration::from_secs(4)).await;
+
+    assert_eq!(interval.execution_count(), 4);
+    assert_eq!(interval.skip_count(), 4);
+    assert_eq!(slow.execution_count(), 2);
+    assert_eq!(slow.skip_count(), 2);
+    scheduler.stop();
+}
+
+#[derive(Debug, Clone)]
+struct OutcomeTask {
+    outcomes: Arc<std::sync::Mutex<Vec<bool>>>,
+}
+
+#[async_trait]
+impl Notifiable for OutcomeTask {
+    fn get_task(&self) -> Task {
+        Task::Interval(1, None)
+    }
+
+    async fn on_time(&self, _cancel: CancellationToken) {
+        self.outcomes.lock().unwrap().push(true);
+    }
+
+    async fn on_skip(&self, _cancel: CancellationToken) {
+        self.outcomes.lock().unwrap().push(false);
+    }
+}
+
+async fn chaos_outcomes(seed: u64) -> Vec<bool> {
+    let scheduler = Scheduler::builder()
+        .chaos_skip_probability(0.5)
+        .rng_seed(seed)
+        .build();
+    let task = OutcomeTask {
+        outcomes: Arc::new(std::sync::Mutex::new(Vec::new())),
+    };
+
+    scheduler.run(task.clone()).await;
+    tokio::time::sleep(Duration::from_millis(20500)).await;
+    scheduler.stop();
+
+    task.outcomes.lock().unwrap().clone()
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_chaos_skip_seeded() {
+    let first = chaos_outcomes(42).await;
+    let second = chaos_outcomes(42).await;
+
+    assert_eq!(first.len(), 20);
+    assert_eq!(first, second);
+    assert!(first.contains(&true));
+    assert!(first.contains(&false));
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_recent_history() {
+    let scheduler = Scheduler::builder().history(3).build();
+    assert!(scheduler.recent_history().is_empty());
+
+    let skip = Some(vec![Skip::Day(vec![1, 2, 3, 4, 5, 6, 7])]);
+    let skipped = scheduler.run(TestTask::new(Task::Wait(1, skip))).await;
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+
+    let history = scheduler.recent_history();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].id, skipped.id());
+    assert_eq!(history[0].outcome, Outcome::Skipped);
+
+    let task = TestTask::new(Task::Interval(1, None));
+    let handle = scheduler.run(task.clone()).await;
+    tokio::time::sleep(Duration::from_millis(4500)).await;
+    scheduler.stop();
+
+    let history = scheduler.recent_history();
+    assert_eq!(task.execution_count(), 4);
+    assert_eq!(history.len(), 3);
+    assert!(history.iter().all(|entry| entry.id == handle.id()));
+    assert!(history.iter().all(|entry| entry.name == "TestTask"));
+    assert!(history.iter().all(|entry| entry.outcome == Outcome::Fired));
+    assert!(history.windows(2).all(|pair| pair[0].at <= pair[1].at));
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_run_for_fires() {
+    let scheduler = Scheduler::new();
+    let task = TestTask::new(Task::Interval(1, None));
+
+    let handle = scheduler.run_for_fires(task.clone(), 3).await;
+    tokio::time::sleep(Duration::from_millis(2500)).await;
+    assert_eq!(task.execution_count(), 2);
+    assert!(!handle.is_cancelled());
+
+    tokio::time::sleep(Duration::from_secs(5)).await;
+    assert_eq!(task.execution_count(), 3);
+    assert!(handle.is_cancelled());
+    assert!(!scheduler.get_cancel().is_cancelled());
+}
+
+#[derive(Debug, Clone)]
+struct CountedTask {
+    failures: u32,
+    calls: Arc<AtomicU32>,
+    successes_only: bool,
+    /// fail by panicking instead of returning an error
+    panics: bool,
+}
+
+#[async_trait]
+impl Notifiable for CountedTask {
+    fn get_task(&self) -> Task {
+        Task::Interval(1, None)
+    }
+
+    async fn on_time_fallible(&self, _cancel: CancellationToken) -> Result<(), TaskError> {
+        if self.calls.fetch_add(1, Ordering::SeqCst) < self.failures {
+            if self.panics {
+                panic!("service down");
+            }
+            return Err("service down".into());
+        }
+        Ok(())
+    }
+
+    async fn on_panic(&self, _message: &str, _cancel: CancellationToken) -> PanicAction {
+        PanicAction::Continue
+    }
+
+    fn count_successes_only(&self) -> bool {
+        self.successes_only
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_run_for_fires_successes_only() {
+    let scheduler = Scheduler::new();
+    let task = CountedTask {
+        failures: 2,
+        calls: Arc::new(AtomicU32::new(0)),
+        successes_only: true,
+        panics: false,
+    };
+
+    let handle = scheduler.run_for_fires(task.clone(), 3).await;
+    tokio::time::sleep(Duration::from_secs(10)).await;
+    assert_eq!(task.calls.load(Ordering::SeqCst), 5);
+    assert!(handle.is_cancelled());
+
+    // failures count by default
+    let task = CountedTask {
+        successes_only: false,
+        calls: Arc::new(AtomicU32::new(0)),
+        ..task
+    };
+    let handle = scheduler.run_for_fires(task.clone(), 3).await;
+    tokio::time::sleep(Duration::from_secs(10)).await;
+    assert_eq!(task.calls.load(Ordering::SeqCst), 3);
+    assert!(handle.is_cancelled());
+
+    // panics are left out like failures
+    let task = CountedTask {
+        successes_only: true,
+        panics: true,
+        calls: Arc::new(AtomicU32::new(0)),
+        ..task
+    };
+    let handle = scheduler.run_for_fires(task.clone(), 3).await;
+    tokio::time::sleep(Duration::from_secs(10)).await;
+    assert_eq!(task.calls.load(Ordering::SeqCst), 5);
+    assert!(handle.is_cancelled());
+
+    // and count by default
+    let task = CountedTask {
+        successes_only: false,
+        calls: Arc::new(AtomicU32::new(0)),
+        ..task
+    };
+    let handle = scheduler.run_for_fires(task.clone(), 3).await;
+    tokio::time::sleep(Duration::from_secs(10)).await;
+    assert_eq!(task.calls.load(Ordering::SeqCst), 3);
+    assert!(handle.is_cancelled());
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_reschedule() {
+    let scheduler = Scheduler::new();
+    let task = TestTask::new(Task::Interval(5, None));
+    let handle = scheduler.run(task.clone()).await;
+
+    tokio::time::sleep(Duration::from_millis(5500)).await;
+    assert_eq!(task.execution_count(), 1);
+
+    let id = handle.id();
+    let old = handle.task();
+    scheduler
+        .reschedule(&handle, Task::Interval(1, None))
+        .await
+        .unwrap();
+    assert_eq!(handle.id(), id);
+    assert_eq!(*handle.task(), Task::Interval(1, None));
+    // a schedule taken before stays the one it was
+    assert_eq!(*old, Task::Interval(5, None));
+    assert_eq!(scheduler.active_task_count(), 1);
+
+    // the faster cadence takes over right away, the old loop is gone
+    tokio::time::sleep(Duration::from_millis(4500)).await;
+    assert_eq!(task.execution_count(), 5);
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    assert_eq!(task.execution_count(), 6);
+
+    handle.cancel();
+    assert!(
+        scheduler
+            .reschedule(&handle, Task::Interval(5, None))
+            .await
+            .is_err()
+    );
+    let other = Scheduler::new();
+    let handle = scheduler.run(TestTask::new(Task::Wait(5, None))).await;
+    assert!(
+        other
+            .reschedule(&handle, Task::Interval(5, None))
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_reload_keeps_interval_phase() {
+    let scheduler = Scheduler::new();
+    let task = TestTask::new(Task::Interval(5, None));
+    let handle = scheduler.run(task.clone()).await;
+    tokio::time::sleep(Duration::from_millis(2500)).await;
+    let next = handle.next_run();
+
+    scheduler
+        .reschedule(&handle, Task::Interval(5, None))
+        .await
+        .unwrap();
+    let reloaded = scheduler.reconcile(vec![task.clone()]).await;
+    assert_eq!(reloaded[0].id(), handle.id());
+    assert_eq!(handle.next_run(), next);
+
+    // still fires 5s after the start, not 5s after the reload
+    tokio::time::sleep(Duration::from_secs(3)).await;
+    assert_eq!(task.execution_count(), 1);
+
+    // a changed period restarts the timer
+    scheduler
+        .reschedule(&handle, Task::Interval(4, None))
+        .await
+        .unwrap();
+    assert_ne!(handle.next_run(), next);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_subscribe_task_events() {
+    let scheduler = Scheduler::new();
+    let mut events = scheduler.subscribe();
+
+    let handle = scheduler
+        .run_for_fires(TestTask::new(Task::IntervalMillis(200, None)), 3)
+        .await;
+    let id = handle.id();
+    assert_eq!(
+        events.recv().await.unwrap(),
+        TaskEvent::Started {
+            id,
+            name: "TestTask".to_string()
+        }
+    );
+    for _ in 0..3 {
+        let event = tokio::time::timeout(Duration::from_secs(1), events.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(matches!(event, TaskEvent::Fired { id: fired, .. } if fired == id));
+    }
+    assert_eq!(events.recv().await.unwrap(), TaskEvent::Stopped { id });
+
+    let skipped = scheduler
+        .run(TestTask::new(Task::Wait(
+            1,
+            Some(vec![Skip::Day(vec![1, 2, 3, 4, 5, 6, 7])]),
+        )))
+        .await;
+    let _started = events.recv().await.unwrap();
+    assert_eq!(
+        events.recv().await.unwrap(),
+        TaskEvent::Skipped {
+            id: skipped.id(),
+            reason: SkipReason::Rule
+        }
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_lifecycle_channel() {
+    let scheduler = Scheduler::new();
+    let mut events = scheduler.lifecycle_channel(1);
+    let task = TestTask::new(Task::IntervalMillis(200, None));
+    let handle = scheduler.run_for_fires(task.clone(), 3).await;
+    let id = handle.id();
+
+    // the full channel holds the task back after its first fire
+    tokio::time::sleep(Duration::from_secs(2)).await;
+    assert_eq!(task.execution_count(), 1);
+
+    let mut received = Vec::new();
+    while let Some(event) = events.recv().await {
+        let stopped = event == TaskEvent::Stopped { id };
+        received.push(event);
+        if stopped {
+            break;
+        }
+    }
+    assert_eq!(task.execution_count(), 3);
+    assert_eq!(received.len(), 5);
+    assert!(matches!(received[0], TaskEvent::Started { .. }));
+    assert!(
+        received[1..4]
+            .iter()
+            .all(|event| matches!(event, TaskEvent::Fired { .. }))
+    );
+    let times = received[1..4]
+        .iter()
+        .map(|event| match event {
+            TaskEvent::Fired { at, .. } => *at,
+            _ => unreachable!(),
+        })
+        .collect::<Vec<_>>();
+    assert!(times.is_sorted());
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_try_lifecycle_channel() {
+    let scheduler = Scheduler::new();
+    let mut events = scheduler.try_lifecycle_channel(1);
+    let task = TestTask::new(Task::IntervalMillis(200, None));
+    scheduler.run_for_fires(task.clone(), 3).await;
+
+    // nothing waits for the consumer, the events not fitting are dropped
+    tokio::time::sleep(Duration::from_secs(2)).await;
+    assert_eq!(task.execution_count(), 3);
+    assert!(matches!(
+        events.recv().await,
+        Some(TaskEvent::Started { .. })
+    ));
+    assert!(events.try_recv().is_err());
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_watchdog() {
+    let gate = Arc::new(AtomicBool::new(true));
+    let scheduler = Scheduler::builder().gate(gate.clone()).build();
+    let task = TestTask::new(Task::Interval(2, None));
+    let handle = scheduler.run(task.clone()).await;
+
+    let missed = Arc::new(AtomicU32::new(0));
+    scheduler.watchdog(&handle, Duration::from_secs(3), {
+        let missed = missed.clone();
+        move |_| {
+            missed.fetch_add(1, Ordering::SeqCst);
+        }
+    });
+
+    tokio::time::sleep(Duration::from_millis(5500)).await;
+    assert_eq!(task.execution_count(), 2);
+    assert_eq!(missed.load(Ordering::SeqCst), 0);
+
+    // closed, the fires at 6s and 8s are skipped, 3s after the last fire at 4s is missed
+    gate.store(false, Ordering::SeqCst);
+    tokio::time::sleep(Duration::from_secs(2)).await;
+    assert_eq!(missed.load(Ordering::SeqCst), 1);
+    tokio::time::sleep(Duration::from_secs(3)).await;
+    assert_eq!(missed.load(Ordering::SeqCst), 2);
+
+    gate.store(true, Ordering::SeqCst);
+    handle.cancel();
+    tokio::time::sleep(Duration::from_secs(10)).await;
+    assert_eq!(missed.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_run_after() {
+    let scheduler = Scheduler::new();
+    let task = TestTask::new(Task::Interval(1, None));
+    let handle = scheduler
+        .run_after(Duration::from_secs(1), task.clone())
+        .await;
+
+    // the interval starts after the delay
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+    assert_eq!(task.execution_count(), 0);
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    assert_eq!(task.execution_count(), 1);
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    assert_eq!(task.execution_count(), 2);
+    handle.cancel();
+
+    // cancelled during the delay, it never starts
+    let task = TestTask::new(Task::Interval(1, None));
+    let handle = scheduler
+        .run_after(Duration::from_secs(5), task.clone())
+        .await;
+    handle.cancel();
+    tokio::time::sleep(Duration::from_secs(10)).await;
+    assert_eq!(task.execution_count(), 0);
+    assert_eq!(scheduler.active_task_count(), 0);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_run_after_next_run() {
+    let scheduler = Scheduler::new();
+    let task = TestTask::new(Task::Interval(10, None));
+    let handle = scheduler.run_after(Duration::from_secs(3600), task).await;
+
+    // during the delay the first run is reported after it, not from the spawn
+    let expected = handle.created_at() + time::Duration::seconds(3610);
+    let next = handle.next_run().unwrap();
+    assert!((next - expected).abs() < time::Duration::seconds(1));
+    handle.cancel();
+
+    // a task started by a fire has no next run before that fire
+    let import = TestTask::new(Task::Interval(100, None));
+    let import_handle = scheduler.run(import).await;
+    let cleanup = TestTask::new(Task::Interval(1, None));
+    let cleanup_handle = scheduler
+        .run_after_fire(&import_handle, Duration::from_secs(1), cleanup)
+        .await;
+    assert_eq!(cleanup_handle.next_run(), None);
+    import_handle.cancel();
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_run_after_fire() {
+    let scheduler = Scheduler::new();
+    let import = TestTask::new(Task::Interval(1, None));
+    let import_handle = scheduler.run_for_fires(import.clone(), 2).await;
+    let cleanup = TestTask::new(Task::Wait(1, None));
+    let cleanup_handle = scheduler
+        .run_after_fire(&import_handle, Duration::from_secs(10), cleanup.clone())
+        .await;
+
+    // the import fires at 1s and 2s, the cleanup starts 10s after the last fire
+    tokio::time::sleep(Duration::from_millis(11500)).await;
+    assert_eq!(import.execution_count(), 2);
+    assert_eq!(cleanup.execution_count(), 0);
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    assert_eq!(cleanup.execution_count(), 0);
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    assert_eq!(cleanup.execution_count(), 1);
+    cleanup_handle.cancel();
+
+    // a task stopping without a fire never starts the other one
+    let import = TestTask::new(Task::Interval(100, None));
+    let import_handle = scheduler.run(import).await;
+    let cleanup = TestTask::new(Task::Wait(1, None));
+    let cleanup_handle = scheduler
+        .run_after_fire(&import_handle, Duration::from_secs(1), cleanup.clone())
+        .await;
+    import_handle.cancel();
+    tokio::time::sleep(Duration::from_secs(10)).await;
+    assert_eq!(cleanup.execution_count(), 0);
+    assert!(cleanup_handle.is_cancelled());
+
+    // a handle of another scheduler never matches a task of this one with the same id
+    let (scheduler, other) = (Scheduler::new(), Scheduler::new());
+    let local = TestTask::new(Task::Interval(1, None));
+    let local_handle = scheduler.run(local.clone()).await;
+    let foreign = other.run(TestTask::new(Task::Interval(100, None))).await;
+    assert_eq!(foreign.id(), local_handle.id());
+    let cleanup = TestTask::new(Task::Wait(1, None));
+    let cleanup_handle = scheduler
+        .run_after_fire(&foreign, Duration::from_secs(1), cleanup.clone())
+        .await;
+    tokio::time::sleep(Duration::from_secs(5)).await;
+    assert!(local.execution_count() > 0);
+    assert_eq!(cleanup.execution_count(), 0);
+    assert!(cleanup_handle.is_cancelled());
+    local_handle.cancel();
+    foreign.cancel();
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_run_for_fires_sub_second() {
+    let scheduler = Scheduler::new();
+    let task = TestTask::new(Task::IntervalMillis(200, None));
+
+    let handle = scheduler.run_for_fires(task.clone(), 3).await;
+    tokio::time::sleep(Duration::from_secs(2)).await;
+
+    assert_eq!(task.execution_count(), 3);
+    assert!(handle.is_cancelled());
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_at_computed_fires_daily() {
+    let scheduler = Scheduler::new();
+    let now = OffsetDateTime::now_utc().to_offset(offset!(+8));
+    let fire = now + time::Duration::seconds(2);
+    let at = fire.time();
+    let offsets = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let compute: DailyTime = {
+        let offsets = offsets.clone();
+        Arc::new(move |_, offset| {
+            offsets.lock().unwrap().push(offset);
+            at
+        })
+    };
+    let task = TestTask::new(Task::AtComputed(compute, None));
+
+    let handle = scheduler.run(task.clone()).await;
+    assert_eq!(handle.next_run(), Some(fire));
+
+    // the wall clock stands still while virtual time advances, so the second
+    // fire is a day after the first
+    tokio::time::sleep(Duration::from_secs(24 * 60 * 60 + 10)).await;
+
+    assert_eq!(task.execution_count(), 2);
+    let next = handle.next_run().unwrap();
+    assert_eq!(next.time(), at);
+    assert_eq!(next.date(), fire.date() + time::Duration::days(2));
+    assert!(offsets.lock().unwrap().iter().all(|offset| *offset == 480));
+}
+
+#[derive(Debug)]
+struct HangingTask;
+
+#[async_trait]
+impl Notifiable for HangingTask {
+    fn get_task(&self) -> Task {
+        Task::Wait(0, None)
+    }
+
+    async fn on_time(&self, _cancel: CancellationToken) {
+        tokio::time::sleep(Duration::from_secs(10)).await;
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_shutdown_timeout_aborts_hung_task() {
+    let scheduler = Scheduler::new();
+    let hanging = scheduler.run(HangingTask).await;
+    let idle = scheduler.run(TestTask::new(Task::Interval(60, None))).await;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let start = tokio::time::Instant::now();
+    let aborted = scheduler.shutdown_timeout(Duration::from_millis(500)).await;
+
+    assert!(start.elapsed() < Duration::from_secs(1));
+    assert_eq!(aborted.len(), 1);
+    assert_eq!(aborted[0].id(), hanging.id());
+    assert!(idle.is_cancelled());
+}
+
+#[derive(Debug, Clone)]
+struct AdaptiveTask {
+    task: Task,
+    calls: Arc<std::sync::Mutex<Vec<tokio::time::Instant>>>,
+}
+
+#[async_trait]
+impl Notifiable for AdaptiveTask {
+    fn get_task(&self) -> Task {
+        self.task.clone()
+    }
+
+    async fn on_time_adaptive(
+        &self,
+        _cancel: CancellationToken,
+    ) -> Result<Option<Duration>, TaskError> {
+        self.calls.lock().unwrap().push(tokio::time::Instant::now());
+        Ok(Some(Duration::from_secs(1)))
+    }
+
+    fn delay_bounds(&self) -> std::ops::RangeInclusive<Duration> {
+        Duration::from_secs(2)..=Duration::from_secs(60)
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_interval_adaptive_delay() {
+    let scheduler = Scheduler::new();
+    let task = AdaptiveTask {
+        task: Task::Interval(10, None),
+        calls: Arc::new(std::sync::Mutex::new(Vec::new())),
+    };
+    let start = tokio::time::Instant::now();
+
+    scheduler.run(task.clone()).await;
+    tokio::time::sleep(Duration::from_millis(14500)).await;
+    scheduler.stop();
+
+    let calls = task.calls.lock().unwrap();
+    let offsets = calls
+        .iter()
+        .map(|call| (*call - start).as_secs())
+        .collect::<Vec<_>>();
+    // the first trigger waits for the interval, then the hint clamped to 2s is used
+    assert_eq!(offsets, vec![10, 12, 14]);
+}
+
+#[derive(Debug, Clone, Default)]
+struct HugeDelayTask {
+    /// fail each call, opening a circuit with a huge cooldown, instead of hinting a
+    /// huge delay
+    fail: bool,
+    calls: Arc<AtomicU32>,
+}
+
+#[async_trait]
+impl Notifiable for HugeDelayTask {
+    fn get_task(&self) -> Task {
+        Task::Interval(1, None)
+    }
+
+    async fn on_time_adaptive(
+        &self,
+        _cancel: CancellationToken,
+    ) -> Result<Option<Duration>, TaskError> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        if self.fail {
+            return Err("down".into());
+        }
+        Ok(Some(Duration::MAX))
+    }
+
+    fn circuit_breaker(&self) -> Option<CircuitBreaker> {
+        Some(CircuitBreaker::new(1, Duration::MAX))
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_interval_huge_delay() {
+    let scheduler = Scheduler::new();
+    for fail in [false, true] {
+        let task = HugeDelayTask {
+            fail,
+            ..Default::default()
+        };
+        let handle = scheduler.run(task.clone()).await;
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+        assert_eq!(task.calls.load(Ordering::SeqCst), 1);
+
+        // the delay is clamped instead of overflowing, the task keeps waiting
+        tokio::time::sleep(Duration::from_secs(3600)).await;
+        assert_eq!(task.calls.load(Ordering::SeqCst), 1);
+        assert!(!handle.is_cancelled());
+        let next = handle.next_run().unwrap();
+        assert!(
+            next - handle.created_at() > time::Duration::days(365),
+            "{next}"
+        );
+        handle.cancel();
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_huge_wait_and_interval() {
+    let scheduler = Scheduler::new();
+    for schedule in [
+        Task::Wait(1_000_000_000_000, None),
+        Task::Wait(u64::MAX, Some(vec![Skip::Day(vec![6])])),
+        Task::Interval(u64::MAX, None),
+        Task::IntervalMillis(u64::MAX, None),
+    ] {
+        // registering does not panic, and the next run is never in the past
+        let handle = scheduler.run(TestTask::new(schedule.clone())).await;
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        assert!(
+            handle
+                .next_run()
+                .is_none_or(|next| next > handle.created_at()),
+            "{schedule:?}"
+        );
+        handle.cancel();
+
+        // nor do the previews going past the representable range
+        let until = OffsetDateTime::now_utc() + time::Duration::days(365);
+        for time in scheduler.next_run_times(TestTask::new(schedule.clone()), 3) {
+            assert!(time > handle.created_at(), "{schedule:?}");
+        }
+        assert!(
+            scheduler
+                .simulate(TestTask::new(schedule.clone()), until)
+                .is_empty()
+        );
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_huge_wait_next_run_is_clamped() {
+    let scheduler = Scheduler::new();
+    // the loop sleeps for about 30 years, the reported next run follows it
+    for schedule in [
+        Task::Wait(1_000_000_000_000, None),
+        Task::Interval(u64::MAX, None),
+    ] {
+        let handle = scheduler.run(TestTask::new(schedule.clone())).await;
+        let next = handle.next_run().unwrap();
+        let ahead = next - handle.created_at();
+        assert!(
+            ahead > time::Duration::days(29 * 365) && ahead < time::Duration::days(31 * 365),
+            "{schedule:?} {next}"
+        );
+        handle.cancel();
+    }
+    assert!(
+        Task::Wait(u64::MAX, None)
+            .get_next_run_time::<TestTask>(0)
+            .is_some()
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_reconcile() {
+    let scheduler = Scheduler::new();
+    let a = TestTask::new(Task::Interval(1, None));
+    let b = TestTask::new(Task::Interval(2, None));
+    let before = scheduler.reconcile(vec![a.clone(), b.clone()]).await;
+    assert_eq!(before.len(), 2);
+
+    let c = TestTask::new(Task::Interval(3, None));
+    let b_again = TestTask::new(Task::Interval(2, None));
+    let after = scheduler.reconcile(vec![b_again.clone(), c.clone()]).await;
+
+    assert!(before[0].is_cancelled());
+    assert_eq!(after[0].id(), before[1].id());
+    assert!(!after[0].is_cancelled());
+    assert_ne!(after[1].id(), before[0].id());
+    assert_eq!(*after[1].task(), Task::Interval(3, None));
+
+    tokio::time::sleep(Duration::from_millis(6500)).await;
+    assert_eq!(a.execution_count(), 0);
+    // the running instance of B is kept, the new one is never started
+    assert_eq!(b.execution_count(), 3);
+    assert_eq!(b_again.execution_count(), 0);
+    assert_eq!(c.execution_count(), 2);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_finished_task_leaves_registry() {
+    let scheduler = Scheduler::new();
+    let task = TestTask::new(Task::Wait(1, None));
+    let handle = scheduler.run(task.clone()).await;
+    let window = Duration::from_secs(3600);
+    assert_eq!(scheduler.to_ics(window).matches("BEGIN:VEVENT").count(), 1);
+    assert_eq!(scheduler.active_task_count(), 1);
+
+    // the handler does not cancel the task, its loop just ends
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+    assert_eq!(task.execution_count(), 1);
+    assert!(!handle.is_cancelled());
+    assert_eq!(scheduler.active_task_count(), 0);
+    assert_eq!(scheduler.to_ics(window).matches("BEGIN:VEVENT").count(), 0);
+
+    // a finished task is not kept as running by reconcile
+    let again = scheduler.reconcile(vec![task.clone()]).await;
+    assert_ne!(again[0].id(), handle.id());
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+    assert_eq!(task.execution_count(), 2);
+}
+
+#[derive(Debug, Clone, Default)]
+struct StaleTask {
+    fired: Arc<AtomicU32>,
+    reasons: Arc<std::sync::Mutex<Vec<SkipReason>>>,
+}
+
+#[async_trait]
+impl Notifiable for StaleTask {
+    fn get_task(&self) -> Task {
+        Task::Interval(1, None)
+    }
+
+    async fn on_time(&self, _cancel: CancellationToken) {
+        self.fired.fetch_add(1, Ordering::SeqCst);
+    }
+
+    async fn on_skip_with_reason(&self, _cancel: CancellationToken, reason: SkipReason) {
+        self.reasons.lock().unwrap().push(reason);
+    }
+
+    fn staleness(&self) -> Option<Duration> {
+        Some(Duration::from_millis(500))
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_stale_trigger_skipped() {
+    let scheduler = Scheduler::new();
+    let task = StaleTask::default();
+    scheduler.run(task.clone()).await;
+
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+    assert_eq!(task.fired.load(Ordering::SeqCst), 1);
+
+    // jump the clock as if the runtime was blocked, the pending trigger wakes up 2s late
+    tokio::time::advance(Duration::from_millis(2500)).await;
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    assert_eq!(task.fired.load(Ordering::SeqCst), 1);
+    assert_eq!(*task.reasons.lock().unwrap(), vec![SkipReason::Stale]);
+
+    // the next trigger is on time again
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    assert_eq!(task.fired.load(Ordering::SeqCst), 2);
+    scheduler.stop();
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_cancel_single_task() {
+    let scheduler = Scheduler::new();
+    let first = TestTask::new(Task::Interval(1, None));
+    let second = TestTask::new(Task::Interval(1, None));
+    let first_handle = scheduler.run(first.clone()).await;
+    let second_handle = scheduler.run(second.clone()).await;
+
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+    first_handle.cancel();
+    tokio::time::sleep(Duration::from_secs(3)).await;
+
+    assert!(first_handle.is_cancelled());
+    assert!(!second_handle.is_cancelled());
+    assert_eq!(first.execution_count(), 1);
+    assert_eq!(second.execution_count(), 4);
+
+    scheduler.stop();
+    assert!(second_handle.is_cancelled());
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_at_task_current_second_fires() {
+    let scheduler = Scheduler::new();
+    let now = OffsetDateTime::now_utc().to_offset(offset!(+8));
+    let at = now.time().replace_nanosecond(0).unwrap();
+    let task = TestTask::new(Task::At(at, None));
+
+    scheduler.run(task.clone()).await;
+    tokio::time::sleep(Duration::from_secs(2)).await;
+
+    assert_eq!(task.execution_count(), 1);
+    scheduler.stop();
+}
+
+#[tokio::test]
+async fn test_interval_phased_aligned_across_schedulers() {
+    let anchor = OffsetDateTime::now_utc().to_offset(offset!(+8));
+    let task = Task::IntervalPhased(1, anchor, None);
+
+    let first = Scheduler::new();
+    let first_handle = first.run(TestTask::new(task.clone())).await;
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    let second = Scheduler::new();
+    let second_handle = second.run(TestTask::new(task)).await;
+
+    let next = second_handle.next_run().unwrap();
+    assert_eq!(first_handle.next_run(), Some(next));
+    assert_eq!((next - anchor).subsec_nanoseconds(), 0);
+
+    first.stop();
+    second.stop();
+}
+
+#[derive(Debug, Clone, Default)]
+struct WarmupTask {
+    events: Arc<std::sync::Mutex<Vec<Option<SkipReason>>>>,
+}
+
+#[async_trait]
+impl Notifiable for WarmupTask {
+    fn get_task(&self) -> Task {
+        Task::Interval(1, None)
+    }
+
+    async fn on_time(&self, _cancel: CancellationToken) {
+        self.events.lock().unwrap().push(None);
+    }
+
+    async fn on_skip_with_reason(&self, _cancel: CancellationToken, reason: SkipReason) {
+        self.events.lock().unwrap().push(Some(reason));
+    }
+
+    fn skip_first(&self) -> u64 {
+        2
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct OverlapTask {
+    events: Arc<std::sync::Mutex<Vec<Option<SkipReason>>>>,
+}
+
+#[async_trait]
+impl Notifiable for OverlapTask {
+    fn get_task(&self) -> Task {
+        Task::FixedRate(1, None)
+    }
+
+    async fn on_time(&self, _cancel: CancellationToken) {
+        self.events.lock().unwrap().push(None);
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+    }
+
+    async fn on_skip_with_reason(&self, _cancel: CancellationToken, reason: SkipReason) {
+        self.events.lock().unwrap().push(Some(reason));
+    }
+
+    fn skip_if_running(&self) -> bool {
+        true
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_skip_if_running() {
+    let scheduler = Scheduler::new();
+    let task = OverlapTask::default();
+    scheduler.run(task.clone()).await;
+
+    // fires at 1s, 3s and 5s, the ticks at 2s and 4s come during a running handler
+    tokio::time::sleep(Duration::from_millis(5200)).await;
+    scheduler.stop();
+
+    assert_eq!(
+        *task.events.lock().unwrap(),
+        vec![
+            None,
+            Some(SkipReason::Overlap),
+            None,
+            Some(SkipReason::Overlap),
+            None
+        ]
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_skip_first_triggers() {
+    let scheduler = Scheduler::new();
+    let task = WarmupTask::default();
+    scheduler.run(task.clone()).await;
+
+    tokio::time::sleep(Duration::from_millis(4500)).await;
+    scheduler.stop();
+
+    assert_eq!(
+        *task.events.lock().unwrap(),
+        vec![
+            Some(SkipReason::Warmup),
+            Some(SkipReason::Warmup),
+            None,
+            None
+        ]
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_interval_drift_stats() {
+    let scheduler = Scheduler::new();
+    let handle = scheduler.run(TestTask::new(Task::Interval(1, None))).await;
+    assert_eq!(handle.drift_stats(), None);
+
+    tokio::time::sleep(Duration::from_millis(3500)).await;
+    scheduler.stop();
+
+    let stats = handle.drift_stats().unwrap();
+    assert_eq!(stats.count, 3);
+    assert!(stats.min <= stats.mean && stats.mean <= stats.max);
+    assert!(stats.max < Duration::from_millis(5));
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_interval_millis() {
+    let scheduler = Scheduler::new();
+    let task = TestTask::new(Task::IntervalMillis(200, None));
+
+    scheduler.run(task.clone()).await;
+
+    tokio::time::sleep(Duration::from_millis(1050)).await;
+    assert_eq!(task.execution_count(), 5);
+}
+
+#[derive(Debug)]
+struct NamedTask(&'static str, Task);
+
+#[async_trait]
+impl Notifiable for NamedTask {
+    fn get_task(&self) -> Task {
+        self.1.clone()
+    }
+
+    fn name(&self) -> String {
+        self.0.to_string()
+    }
+
+    async fn on_time(&self, _cancel: CancellationToken) {}
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_next_fire() {
+    let scheduler = Scheduler::new();
+    assert_eq!(scheduler.next_fire().await, None);
+
+    scheduler
+        .run(NamedTask("slow", Task::Interval(60, None)))
+        .await;
+    scheduler
+        .run(NamedTask("fast", Task::Interval(2, None)))
+        .await;
+
+    let fired = tokio::select! {
+        name = scheduler.next_fire() => name,
+        _ = tokio::time::sleep(Duration::from_secs(1)) => None,
+    };
+    assert_eq!(fired, None);
+
+    let fired = tokio::select! {
+        name = scheduler.next_fire() => name,
+        _ = tokio::time::sleep(Duration::from_secs(5)) => None,
+    };
+    assert_eq!(fired.as_deref(), Some("fast"));
+
+    scheduler.stop();
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    assert_eq!(scheduler.next_fire().await, None);
+}
+
+#[derive(Debug, Clone)]
+struct SlowTask {
+    start: tokio::time::Instant,
+    fires: Arc<std::sync::Mutex<Vec<Duration>>>,
+}
+
+#[async_trait]
+impl Notifiable for SlowTask {
+    fn get_task(&self) -> Task {
+        Task::FixedRate(1, None)
+    }
+
+    async fn on_time(&self, _cancel: CancellationToken) {
+        self.fires.lock().unwrap().push(self.start.elapsed());
+        tokio::time::sleep(Duration::from_millis(300)).await;
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_fixed_rate_does_not_drift() {
+    let scheduler = Scheduler::new();
+    let task = SlowTask {
+        start: tokio::time::Instant::now(),
+        fires: Arc::default(),
+    };
+
+    let handle = scheduler.run(task.clone()).await;
+    tokio::time::sleep(Duration::from_millis(5500)).await;
+    handle.cancel();
+
+    let fires = task.fires.lock().unwrap().clone();
+    assert_eq!(fires.len(), 5);
+    for (n, fire) in fires.iter().enumerate() {
+        let expected = Duration::from_secs(n as u64 + 1);
+        assert!(
+            fire.abs_diff(expected) < Duration::from_millis(50),
+            "fire {n} at {fire:?}"
+        );
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_active_task_count() {
+    let scheduler = Scheduler::new();
+    assert_eq!(scheduler.active_task_count(), 0);
+
+    for _ in 0..3 {
+        scheduler.run(TestTask::new(Task::Wait(1, None))).await;
+    }
+    assert_eq!(scheduler.active_task_count(), 3);
+
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+    assert_eq!(scheduler.active_task_count(), 0);
+
+    // cancelled and skipped tasks are counted out too
+    let interval = scheduler.run(TestTask::new(Task::Interval(1, None))).await;
+    let skipped = Task::Wait(1, Some(vec![Skip::Day((1..=7).collect())]));
+    scheduler.run(TestTask::new(skipped)).await;
+    assert_eq!(scheduler.active_task_count(), 2);
+
+    interval.cancel();
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+    assert_eq!(scheduler.active_task_count(), 0);
+}
+
+#[derive(Debug)]
+struct SquareTask(u64);
+
+#[async_trait]
+impl Notifiable for SquareTask {
+    fn get_task(&self) -> Task {
+        Task::Wait(1, None)
+    }
+}
+
+#[async_trait]
+impl Producer for SquareTask {
+    type Output = u64;
+
+    async fn produce(&self, _cancel: CancellationToken) -> u64 {
+        self.0 * self.0
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_run_collecting() {
+    let scheduler = Scheduler::new();
+    let (tx, mut rx) = tokio::sync::mpsc::channel(4);
+
+    let handle = scheduler.run_collecting(SquareTask(7), tx).await;
+    assert_eq!(handle.name(), "SquareTask");
+
+    let value = tokio::time::timeout(Duration::from_secs(2), rx.recv()).await;
+    assert_eq!(value.unwrap(), Some(49));
+    // the wait task is done, so the sender is dropped
+    assert_eq!(rx.recv().await, None);
+}
+
+#[derive(Debug, Clone, Default)]
+struct FlakyProducer {
+    calls: Arc<AtomicU32>,
+    panics: Arc<AtomicU32>,
+}
+
+#[async_trait]
+impl Notifiable for FlakyProducer {
+    fn get_task(&self) -> Task {
+        Task::Interval(1, None)
+    }
+
+    async fn on_panic(&self, _message: &str, _cancel: CancellationToken) -> PanicAction {
+        self.panics.fetch_add(1, Ordering::SeqCst);
+        PanicAction::Continue
+    }
+}
+
+#[async_trait]
+impl Producer for FlakyProducer {
+    type Output = u32;
+
+    async fn produce(&self, _cancel: CancellationToken) -> u32 {
+        let call = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+        if call == 1 {
+            panic!("first call");
+        }
+        call
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_run_collecting_forwards_hooks() {
+    let scheduler = Scheduler::new();
+    let (tx, mut rx) = tokio::sync::mpsc::channel(4);
+    let task = FlakyProducer::default();
+
+    let handle = scheduler.run_collecting(task.clone(), tx).await;
+    // the panic of the first call does not stop the task
+    let value = tokio::time::timeout(Duration::from_secs(3), rx.recv()).await;
+    assert_eq!(value.unwrap(), Some(2));
+    assert_eq!(task.panics.load(Ordering::SeqCst), 1);
+    assert!(!handle.is_cancelled());
+    handle.cancel();
+}
+
+#[derive(Debug, Clone, Default)]
+struct DbWriteTask {
+    written: Arc<AtomicBool>,
+}
+
+#[async_trait]
+impl Notifiable for DbWriteTask {
+    fn get_task(&self) -> Task {
+        Task::Interval(1, None)
+    }
+
+    async fn on_time(&self, _cancel: CancellationToken) {
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        self.written.store(true, Ordering::SeqCst);
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_shutdown_awaits_handlers() {
+    let scheduler = Scheduler::new();
+    let task = DbWriteTask::default();
+    let handle = scheduler.run(task.clone()).await;
+
+    // in the middle of the first handler
+    tokio::time::sleep(Duration::from_millis(1200)).await;
+    assert!(!task.written.load(Ordering::SeqCst));
+
+    scheduler.shutdown().await;
+    assert!(task.written.load(Ordering::SeqCst));
+    assert!(handle.is_cancelled());
+}
+
+#[derive(Debug, Clone)]
+struct LateAtTask {
+    at: time::Time,
+    behavior: AtBehavior,
+    fires: Arc<AtomicU32>,
+    skips: Arc<std::sync::Mutex<Vec<SkipReason>>>,
+}
+
+#[async_trait]
+impl Notifiable for LateAtTask {
+    fn get_task(&self) -> Task {
+        Task::At(self.at, None)
+    }
+
+    fn at_behavior(&self) -> AtBehavior {
+        self.behavior
+    }
+
+    async fn on_time(&self, _cancel: CancellationToken) {
+        self.fires.fetch_add(1, Ordering::SeqCst);
+    }
+
+    async fn on_skip_with_reason(&self, _cancel: CancellationToken, reason: SkipReason) {
+        self.skips.lock().unwrap().push(reason);
+    }
+}
+
+async fn run_late_at(behavior: AtBehavior) -> (LateAtTask, TaskHandle, OffsetDateTime) {
+    let scheduler = Scheduler::new();
+    let now = OffsetDateTime::now_utc().to_offset(offset!(+8));
+    let passed = now - time::Duration::seconds(2);
+    let task = LateAtTask {
+        at: passed.time(),
+        behavior,
+        fires: Arc::default(),
+        skips: Arc::default(),
+    };
+    let handle = scheduler.run(task.clone()).await;
+    tokio::time::sleep(Duration::from_secs(10)).await;
+    (task, handle, passed)
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_at_started_late_waits_for_next_occurrence() {
+    let (task, handle, passed) = run_late_at(AtBehavior::NextOccurrence).await;
+
+    assert_eq!(handle.at_behavior(), AtBehavior::NextOccurrence);
+    assert_eq!(task.fires.load(Ordering::SeqCst), 0);
+    assert_eq!(*task.skips.lock().unwrap(), vec![SkipReason::Missed]);
+    assert_eq!(handle.next_run().unwrap(), passed + time::Duration::days(1));
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_at_started_late_catches_up() {
+    let (task, handle, passed) = run_late_at(AtBehavior::CatchUp).await;
+
+    assert_eq!(handle.at_behavior(), AtBehavior::CatchUp);
+    assert_eq!(task.fires.load(Ordering::SeqCst), 1);
+    assert!(task.skips.lock().unwrap().is_empty());
+    assert_eq!(handle.next_run().unwrap(), passed + time::Duration::days(1));
+}
+
+#[tokio::test]
+async fn test_detect_clustering() {
+    let scheduler = Scheduler::new();
+    for name in ["backup", "report", "cleanup", "rotate"] {
+        scheduler
+            .run(NamedTask(name, Task::At(time::Time::MIDNIGHT, None)))
+            .await;
+    }
+    scheduler
+        .run(NamedTask(
+            "noon",
+            Task::At(time::macros::time!(12:00), None),
+        ))
+        .await;
+    scheduler
+        .run(NamedTask(
+            "late",
+            Task::At(time::macros::time!(00:00:30), None),
+        ))
+        .await;
+
+    let clusters = scheduler.detect_clustering(Duration::from_secs(60), 3);
+    assert_eq!(clusters.len(), 1);
+    let (at, tasks) = clusters[0];
+    assert_eq!(at.time(), time::Time::MIDNIGHT);
+    assert_eq!(tasks, 5);
+
+    // a tighter window leaves the 00:00:30 task out
+    let clusters = scheduler.detect_clustering(Duration::from_secs(10), 3);
+    assert_eq!(clusters.len(), 1);
+    assert_eq!(clusters[0].1, 4);
+
+    assert!(
+        scheduler
+            .detect_clustering(Duration::from_secs(60), 5)
+            .is_empty()
+    );
+    scheduler.stop();
+}
+
+#[derive(Debug, Clone)]
+struct TimedTask {
+    task: Task,
+    start: tokio::time::Instant,
+    fires: Arc<std::sync::Mutex<Vec<Duration>>>,
+}
+
+#[async_trait]
+impl Notifiable for TimedTask {
+    fn get_task(&self) -> Task {
+        self.task.clone()
+    }
+
+    async fn on_time(&self, _cancel: CancellationToken) {
+        self.fires.lock().unwrap().push(self.start.elapsed());
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_run_with_jitter() {
+    let scheduler = Scheduler::builder().rng_seed(7).build();
+    let task = TimedTask {
+        task: Task::Interval(10, None),
+        start: tokio::time::Instant::now(),
+        fires: Arc::default(),
+    };
+    let max_jitter = Duration::from_secs(2);
+
+    scheduler.run_with_jitter(task.clone(), max_jitter).await;
+    tokio::time::sleep(Duration::from_secs(59)).await;
+    scheduler.stop();
+
+    let fires = task.fires.lock().unwrap().clone();
+    assert_eq!(fires.len(), 5);
+    let jitters = fires
+        .iter()
+        .enumerate()
+        .map(|(n, fire)| *fire - Duration::from_secs(10 * (n as u64 + 1)))
+        .collect::<Vec<_>>();
+    // within the bound on every tick, so the jitter does not add up
+    assert!(
+        jitters.iter().all(|jitter| *jitter <= max_jitter),
+        "{jitters:?}"
+    );
+    assert!(
+        jitters.windows(2).any(|pair| pair[0] != pair[1]),
+        "{jitters:?}"
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_run_ref() {
+    let scheduler = Scheduler::new();
+    let task = TestTask::new(Task::Interval(1, None));
+
+    let handle = scheduler.run_ref(&task).await;
+    tokio::time::sleep(Duration::from_millis(2500)).await;
+
+    // the task stays usable at the call site
+    assert_eq!(task.execution_count(), 2);
+    assert_eq!(*handle.task(), task.get_task());
+    scheduler.stop();
+}
+
+#[derive(Debug, Clone, Default)]
+struct FailingTask {
+    errors: Arc<std::sync::Mutex<Vec<String>>>,
+}
+
+#[async_trait]
+impl Notifiable for FailingTask {
+    fn get_task(&self) -> Task {
+        Task::Wait(1, None)
+    }
+
+    async fn on_time_fallible(&self, _cancel: CancellationToken) -> Result<(), TaskError> {
+        Err("disk full".into())
+    }
+
+    async fn on_error(&self, err: &TaskError, _cancel: CancellationToken) {
+        self.errors.lock().unwrap().push(err.to_string());
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_on_error() {
+    let scheduler = Scheduler::new();
+    let task = FailingTask::default();
+
+    scheduler.run(task.clone()).await;
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+
+    assert_eq!(*task.errors.lock().unwrap(), vec!["disk full".to_string()]);
+}
+
+#[derive(Debug, Clone, Default)]
+struct PanickingTask {
+    keep_going: bool,
+    fires: Arc<AtomicU32>,
+    panics: Arc<std::sync::Mutex<Vec<String>>>,
+}
+
+#[async_trait]
+impl Notifiable for PanickingTask {
+    fn get_task(&self) -> Task {
+        Task::Interval(1, None)
+    }
+
+    async fn on_time(&self, _cancel: CancellationToken) {
+        let fire = self.fires.fetch_add(1, Ordering::SeqCst) + 1;
+        panic!("bad fire {fire}");
+    }
+
+    async fn on_panic(&self, message: &str, _cancel: CancellationToken) -> PanicAction {
+        self.panics.lock().unwrap().push(message.to_string());
+        if self.keep_going {
+            PanicAction::Continue
+        } else {
+            PanicAction::Stop
+        }
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_on_panic_stops_task() {
+    let scheduler = Scheduler::new();
+    let task = PanickingTask::default();
+
+    let handle = scheduler.run(task.clone()).await;
+    tokio::time::sleep(Duration::from_millis(3500)).await;
+
+    assert_eq!(*task.panics.lock().unwrap(), vec!["bad fire 1".to_string()]);
+    assert!(handle.is_cancelled());
+    assert_eq!(task.fires.load(Ordering::SeqCst), 1);
+    assert_eq!(scheduler.active_task_count(), 0);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_on_panic_continues_task() {
+    let scheduler = Scheduler::new();
+    let task = PanickingTask {
+        keep_going: true,
+        ..Default::default()
+    };
+
+    let handle = scheduler.run(task.clone()).await;
+    tokio::time::sleep(Duration::from_millis(3500)).await;
+
+    assert_eq!(
+        *task.panics.lock().unwrap(),
+        vec![
+            "bad fire 1".to_string(),
+            "bad fire 2".to_string(),
+            "bad fire 3".to_string()
+        ]
+    );
+    assert!(!handle.is_cancelled());
+    scheduler.stop();
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_run_for_fires_counts_panics() {
+    let scheduler = Scheduler::new();
+    let task = PanickingTask {
+        keep_going: true,
+        ..Default::default()
+    };
+    let mut events = scheduler.subscribe();
+
+    let handle = scheduler.run_for_fires(task.clone(), 2).await;
+    tokio::time::sleep(Duration::from_secs(10)).await;
+
+    assert_eq!(task.fires.load(Ordering::SeqCst), 2);
+    assert!(handle.is_cancelled());
+    // panicked calls are reported as fires
+    let mut fired = 0;
+    while let Ok(event) = events.try_recv() {
+        if matches!(event, TaskEvent::Fired { .. }) {
+            fired += 1;
+        }
+    }
+    assert_eq!(fired, 2);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_run_within() {
+    let scheduler = Scheduler::new();
+    let now = OffsetDateTime::now_utc().to_offset(offset!(+8));
+    // fires 1s apart from 3s in, the last one at 5s
+    let window = Task::Interval(1, None).within(
+        now + time::Duration::seconds(2),
+        now + time::Duration::milliseconds(5500),
+    );
+    let task = TestTask::new(window);
+
+    let handle = scheduler.run(task.clone()).await;
+    tokio::time::sleep(Duration::from_millis(2500)).await;
+    assert_eq!(task.execution_count(), 0);
+
+    assert!(handle.next_run().is_some());
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    assert_eq!(task.execution_count(), 1);
+    tokio::time::sleep(Duration::from_secs(2)).await;
+    assert_eq!(task.execution_count(), 3);
+
+    // the task is done once the end is reached
+    tokio::time::sleep(Duration::from_secs(20)).await;
+    assert_eq!(task.execution_count(), 3);
+    assert_eq!(handle.next_run(), None);
+    assert_eq!(scheduler.active_task_count(), 0);
+
+    // the wrapped interval keeps its delay hints, 10s after the start then every 2s
+    let now = OffsetDateTime::now_utc();
+    let task = AdaptiveTask {
+        task: Task::Interval(10, None).within(
+            now + time::Duration::seconds(5),
+            now + time::Duration::milliseconds(20500),
+        ),
+        calls: Arc::new(std::sync::Mutex::new(Vec::new())),
+    };
+    let start = tokio::time::Instant::now();
+    scheduler.run(task.clone()).await;
+    tokio::time::sleep(Duration::from_secs(30)).await;
+    let offsets = task
+        .calls
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|call| (*call - start).as_secs_f64().round() as u64)
+        .collect::<Vec<_>>();
+    assert_eq!(offsets, vec![15, 17, 19]);
+    assert_eq!(scheduler.active_task_count(), 0);
+}
+
+#[derive(Debug, Clone)]
+struct AlignedTask(Task);
+
+#[async_trait]
+impl Notifiable for AlignedTask {
+    fn get_task(&self) -> Task {
+        self.0.clone()
+    }
+
+    async fn on_time(&self, _cancel: CancellationToken) {}
+
+    fn align_interval(&self) -> bool {
+        true
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_align_interval() {
+    let scheduler = Scheduler::new();
+
+    let handle = scheduler.run(AlignedTask(Task::Interval(60, None))).await;
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    let first = handle.next_run().unwrap();
+    assert_eq!((first.second(), first.nanosecond()), (0, 0));
+
+    // the next trigger is on the following minute
+    tokio::time::sleep(Duration::from_secs(61)).await;
+    assert_eq!(handle.next_run(), Some(first + time::Duration::MINUTE));
+
+    let handle = scheduler.run(AlignedTask(Task::Interval(900, None))).await;
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    let next = handle.next_run().unwrap();
+    assert_eq!(next.minute() % 15, 0);
+    assert_eq!((next.second(), next.nanosecond()), (0, 0));
+
+    // 7s does not divide an hour, the phase of the start is kept
+    let now = OffsetDateTime::now_utc();
+    let handle = scheduler.run(AlignedTask(Task::Interval(7, None))).await;
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    let next = handle.next_run().unwrap();
+    assert!((next - now - time::Duration::seconds(7)).abs() < time::Duration::seconds(1));
+    scheduler.stop();
+}
+
+#[derive(Debug, Clone, Default)]
+struct FlightTask {
+    key: Option<String>,
+    running: Arc<AtomicU32>,
+    max_running: Arc<AtomicU32>,
+    done: Arc<AtomicU32>,
+}
+
+#[async_trait]
+impl Notifiable for FlightTask {
+    fn get_task(&self) -> Task {
+        Task::Wait(1, None)
+    }
+
+    async fn on_time(&self, _cancel: CancellationToken) {
+        let running = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+        self.max_running.fetch_max(running, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        self.running.fetch_sub(1, Ordering::SeqCst);
+        self.done.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn single_flight_key(&self) -> Option<String> {
+        self.key.clone()
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_single_flight_key() {
+    let scheduler = Scheduler::new();
+    let task = FlightTask {
+        key: Some("report".to_string()),
+        ..Default::default()
+    };
+
+    // both trigger at 1s, the second waits for the first
+    scheduler.run(task.clone()).await;
+    scheduler.run(task.clone()).await;
+    tokio::time::sleep(Duration::from_millis(2500)).await;
+    assert_eq!(task.done.load(Ordering::SeqCst), 1);
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    assert_eq!(task.done.load(Ordering::SeqCst), 2);
+    assert_eq!(task.max_running.load(Ordering::SeqCst), 1);
+
+    // without a key they overlap
+    let task = FlightTask::default();
+    scheduler.run(task.clone()).await;
+    scheduler.run(task.clone()).await;
+    tokio::time::sleep(Duration::from_secs(3)).await;
+    assert_eq!(task.max_running.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_run_all() {
+    let scheduler = Scheduler::new();
+    let tasks: Vec<_> = (1..=3)
+        .map(|wait| TestTask::new(Task::Wait(wait, None)))
+        .collect();
+    let handles = scheduler.run_all(tasks.clone()).await;
+    assert_eq!(handles.len(), 3);
+
+    tokio::time::sleep(Duration::from_millis(3500)).await;
+    assert!(tasks.iter().all(|task| task.execution_count() == 1));
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_run_all_boxed() {
+    let scheduler = Scheduler::new();
+    let waiting = TestTask::new(Task::Wait(1, None));
+    let repeating = TestTask::new(Task::Interval(1, None));
+    let flight = FlightTask::default();
+    let failing = FailingTask::default();
+    let panicking = PanickingTask::default();
+    let tasks: Vec<Box<dyn Notifiable>> = vec![
+        Box::new(waiting.clone()),
+        Box::new(repeating.clone()),
+        Box::new(flight.clone()),
+        Box::new(failing.clone()),
+        Box::new(panicking.clone()),
+    ];
+
+    let handles = scheduler.run_all_boxed(tasks).await;
+    let names: Vec<_> = handles
+        .iter()
+        .map(|handle| handle.name().to_string())
+        .collect();
+    assert_eq!(
+        names,
+        [
+            "TestTask",
+            "TestTask",
+            "FlightTask",
+            "FailingTask",
+            "PanickingTask"
+        ]
+    );
+
+    tokio::time::sleep(Duration::from_millis(2500)).await;
+    assert_eq!(waiting.execution_count(), 1);
+    assert!(repeating.execution_count() >= 1);
+    assert_eq!(flight.done.load(Ordering::SeqCst), 1);
+    // the hooks of the boxed tasks are called
+    assert_eq!(
+        *failing.errors.lock().unwrap(),
+        vec!["disk full".to_string()]
+    );
+    assert_eq!(
+        *panicking.panics.lock().unwrap(),
+        vec!["bad fire 1".to_string()]
+    );
+    scheduler.stop();
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_run_dyn() {
+    let scheduler = Scheduler::new();
+    let counted = TestTask::new(Task::Wait(1, None));
+    let flight = FlightTask::default();
+    let tasks: Vec<Box<dyn Notifiable>> = vec![Box::new(counted.clone()), Box::new(flight.clone())];
+
+    let mut handles = Vec::new();
+    for task in tasks {
+        handles.push(scheduler.run_dyn(task).await);
+    }
+    assert_eq!(handles[1].name(), "FlightTask");
+    assert_eq!(*handles[1].task(), Task::Wait(1, None));
+
+    tokio::time::sleep(Duration::from_millis(2500)).await;
+    assert_eq!(counted.execution_count(), 1);
+    assert_eq!(flight.done.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_maintenance_window() {
+    let scheduler = Scheduler::new();
+    // every minute starts a window of two minutes, so now is always in one
+    scheduler
+        .add_maintenance_window("0 * * * * *", Duration::from_secs(120))
+        .unwrap();
+    let task = TestTask::new(Task::Interval(1, None));
+
+    scheduler.run(task.clone()).await;
+    tokio::time::sleep(Duration::from_millis(3500)).await;
+    assert_eq!(task.execution_count(), 0);
+    assert_eq!(task.skip_count(), 3);
+    scheduler.stop();
+
+    assert!(
+        scheduler
+            .add_maintenance_window("not cron", Duration::from_secs(60))
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn test_maintenance_window_ends() {
+    // start right after a whole second, the window checks the wall clock
+    let now = OffsetDateTime::now_utc();
+    tokio::time::sleep(Duration::from_nanos(
+        1_000_000_000 - now.nanosecond() as u64,
+    ))
+    .await;
+    let now = OffsetDateTime::now_utc().to_offset(offset!(+8));
+
+    let scheduler = Scheduler::new();
+    scheduler
+        .add_maintenance_window(
+            &format!("{} * * * * *", now.second()),
+            Duration::from_millis(1500),
+        )
+        .unwrap();
+    let task = TestTask::new(Task::Interval(1, None));
+
+    scheduler.run(task.clone()).await;
+    tokio::time::sleep(Duration::from_millis(1200)).await;
+    assert_eq!(task.execution_count(), 0);
+    assert_eq!(task.skip_count(), 1);
+
+    // the window is over, the task resumes
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    assert_eq!(task.execution_count(), 1);
+    scheduler.stop();
+}
@@ -0,0 +1,192 @@
+#![cfg(feature = "serde")]
+
+use easy_schedule::prelude::*;
+use std::sync::Arc;
+use time::macros::{date, datetime, time};
+
+fn round_trip(task: Task, json: &str) {
+    assert_eq!(serde_json::to_string(&task).unwrap(), json);
+    assert_eq!(serde_json::from_str::<Task>(json).unwrap(), task);
+}
+
+#[test]
+fn test_task_json() {
+    round_trip(Task::Wait(10, None), r#"{"Wait":[10,null]}"#);
+    round_trip(Task::Interval(5, Some(vec![])), r#"{"Interval":[5,[]]}"#);
+    round_trip(
+        Task::IntervalPhased(300, datetime!(2024-01-01 00:00 +8), None),
+        r#"{"IntervalPhased":[300,"2024-01-01 00:00:00.0 +08:00:00",null]}"#,
+    );
+    round_trip(
+        Task::At(time!(09:30), None),
+        r#"{"At":["09:30:00.0",null]}"#,
+    );
+    round_trip(
+        Task::AtUntil(time!(09:30), date!(2025 - 01 - 01), None),
+        r#"{"AtUntil":["09:30:00.0","2025-01-01",null]}"#,
+    );
+    round_trip(
+        Task::Once(datetime!(2024-12-25 09:00:00.5 +5:30), None),
+        r#"{"Once":["2024-12-25 09:00:00.5 +05:30:00",null]}"#,
+    );
+    round_trip(Task::Hourly(15, None), r#"{"Hourly":[15,null]}"#);
+    round_trip(
+        Task::Weekly(vec![1, 5], time!(09:00), None),
+        r#"{"Weekly":[[1,5],"09:00:00.0",null]}"#,
+    );
+    round_trip(
+        Task::BusinessDayInterval(3, time!(09:00), None),
+        r#"{"BusinessDayInterval":[3,"09:00:00.0",null]}"#,
+    );
+    round_trip(
+        Task::Cron("0 30 9 * * 1-5".to_string(), None),
+        r#"{"Cron":["0 30 9 * * 1-5",null]}"#,
+    );
+}
+
+#[test]
+fn test_skip_json() {
+    let skips = vec![
+        Skip::Date(date!(2024 - 12 - 25)),
+        Skip::DateRange(date!(2024 - 12 - 24), date!(2024 - 12 - 26)),
+        Skip::Day(vec![6, 7]),
+        Skip::DayRange(1, 5),
+        Skip::Time(time!(12:00)),
+        Skip::TimeRange(time!(22:00), time!(06:00)),
+        Skip::TimeRangeExclusive(time!(09:00), time!(17:00)),
+        Skip::FirstOfMonth,
+        Skip::LastOfMonth,
+        Skip::TimeGrid(time!(00:00), 30),
+        Skip::None,
+    ];
+    round_trip(
+        Task::Interval(60, Some(skips)),
+        concat!(
+            r#"{"Interval":[60,["#,
+            r#"{"Date":"2024-12-25"},"#,
+            r#"{"DateRange":["2024-12-24","2024-12-26"]},"#,
+            r#"{"Day":[6,7]},"#,
+            r#"{"DayRange":[1,5]},"#,
+            r#"{"Time":"12:00:00.0"},"#,
+            r#"{"TimeRange":["22:00:00.0","06:00:00.0"]},"#,
+            r#"{"TimeRangeExclusive":["09:00:00.0","17:00:00.0"]},"#,
+            r#""FirstOfMonth","#,
+            r#""LastOfMonth","#,
+            r#"{"TimeGrid":["00:00:00.0",30]},"#,
+            r#""None""#,
+            r#"]]}"#,
+        ),
+    );
+}
+
+#[test]
+fn test_at_computed_not_serializable() {
+    let task = Task::AtComputed(Arc::new(|_, _| time!(09:00)), None);
+    assert!(serde_json::to_string(&task).is_err());
+    assert!(serde_json::from_str::<Task>(r#"{"AtComputed":[null]}"#).is_err());
+}
+
+#[derive(Debug)]
+struct Named {
+    name: &'static str,
+    task: Task,
+}
+
+#[async_trait]
+impl Notifiable for Named {
+    fn get_task(&self) -> Task {
+        self.task.clone()
+    }
+
+    fn name(&self) -> String {
+        self.name.to_string()
+    }
+
+    async fn on_time(&self, _cancel: CancellationToken) {}
+}
+
+fn factory(key: &str, task: Task) -> Option<Box<dyn Notifiable>> {
+    let name = match key {
+        "report" => "report",
+        "cleanup" => "cleanup",
+        _ => return None,
+    };
+    Some(Box::new(Named { name, task }))
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_load_config() {
+    let scheduler = Scheduler::with_timezone_minutes(0);
+    let config = r#"{"tasks": [
+        {"schedule": "interval(60)", "handler": "report"},
+        {"schedule": "at(03:00)", "handler": "cleanup"}
+    ]}"#;
+    let handles = scheduler.load_config_str(config, factory).await.unwrap();
+    assert_eq!(handles.len(), 2);
+    assert_eq!(handles[0].name(), "report");
+    assert_eq!(*handles[0].task(), Task::Interval(60, None));
+    assert_eq!(*handles[1].task(), Task::At(time!(03:00), None));
+
+    let path = std::env::temp_dir().join("easy_schedule_load_config.json");
+    std::fs::write(&path, config).unwrap();
+    let handles = scheduler.load_config(&path, factory).await.unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(handles.len(), 2);
+    scheduler.stop();
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_load_config_errors() {
+    let scheduler = Scheduler::new();
+    let config = r#"{"tasks": [
+        {"schedule": "interval(60)", "handler": "report"},
+        {"schedule": "interval(x)", "handler": "report"},
+        {"schedule": "wait(5)", "handler": "email"}
+    ]}"#;
+    let err = scheduler
+        .load_config_str(config, factory)
+        .await
+        .unwrap_err();
+    assert!(err.starts_with("task 1 (handler 'report'): "), "{err}");
+    assert!(
+        err.contains("task 2 (handler 'email'): unknown handler"),
+        "{err}"
+    );
+    // nothing is scheduled from an invalid config
+    assert_eq!(scheduler.active_task_count(), 0);
+
+    assert!(scheduler.load_config_str("{}", factory).await.is_err());
+    assert!(
+        scheduler
+            .load_config("/nonexistent/easy_schedule.json", factory)
+            .await
+            .unwrap_err()
+            .contains("/nonexistent/easy_schedule.json")
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_debug_json() {
+    let scheduler = Scheduler::with_timezone_minutes(0);
+    let task = Named {
+        name: "report",
+        task: Task::Interval(1, None),
+    };
+    scheduler.run(task).await;
+    tokio::time::sleep(std::time::Duration::from_millis(2500)).await;
+
+    let json: serde_json::Value = serde_json::from_str(&scheduler.debug_json()).unwrap();
+    assert_eq!(json["timezone_minutes"], 0);
+    assert_eq!(json["cancelled"], false);
+    let task = &json["tasks"][0];
+    assert_eq!(task["name"], "report");
+    assert_eq!(task["schedule"], "interval(1)");
+    assert_eq!(task["executions"].as_u64(), Some(2));
+    assert_eq!(task["skips"].as_u64(), Some(0));
+    assert!(task["next_run"].as_str().unwrap().ends_with('Z'));
+    assert!(task["last_run"].is_string());
+
+    scheduler.stop();
+    let json: serde_json::Value = serde_json::from_str(&scheduler.debug_json()).unwrap();
+    assert_eq!(json["cancelled"], true);
+}
@@ -62,6 +62,24 @@ fn test_skip_day_range() {
     assert!(!skip.is_skip(saturday));
 }
 
+#[test]
+fn test_skip_day_range_wraps() {
+    let skip = Skip::DayRange(6, 1); // Saturday to Monday
+    let saturday = OffsetDateTime::new_utc(date!(2024 - 12 - 21), time!(10:00:00));
+    let sunday = OffsetDateTime::new_utc(date!(2024 - 12 - 22), time!(10:00:00));
+    let monday = OffsetDateTime::new_utc(date!(2024 - 12 - 23), time!(10:00:00));
+    let tuesday = OffsetDateTime::new_utc(date!(2024 - 12 - 24), time!(10:00:00));
+    let wednesday = OffsetDateTime::new_utc(date!(2024 - 12 - 25), time!(10:00:00));
+    let friday = OffsetDateTime::new_utc(date!(2024 - 12 - 27), time!(10:00:00));
+
+    assert!(skip.is_skip(saturday));
+    assert!(skip.is_skip(sunday));
+    assert!(skip.is_skip(monday));
+    assert!(!skip.is_skip(tuesday));
+    assert!(!skip.is_skip(wednesday));
+    assert!(!skip.is_skip(friday));
+}
+
 #[test]
 fn test_skip_time() {
     let skip = Skip::Time(time!(14:30:00));
@@ -100,6 +118,49 @@ fn test_skip_time_range_overnight() {
     assert!(!skip.is_skip(afternoon));
 }
 
+#[test]
+fn test_skip_time_range_exclusive_end() {
+    let inclusive = Skip::TimeRange(time!(09:00:00), time!(17:00:00));
+    let exclusive = Skip::TimeRangeExclusive(time!(09:00:00), time!(17:00:00));
+    let start_time = OffsetDateTime::new_utc(date!(2024 - 12 - 25), time!(09:00:00));
+    let before_end = OffsetDateTime::new_utc(date!(2024 - 12 - 25), time!(16:59:59.999));
+    let end_time = OffsetDateTime::new_utc(date!(2024 - 12 - 25), time!(17:00:00));
+
+    assert!(inclusive.is_skip(end_time));
+    assert!(!exclusive.is_skip(end_time));
+
+    assert!(exclusive.is_skip(start_time));
+    assert!(exclusive.is_skip(before_end));
+    assert!(inclusive.is_skip(before_end));
+}
+
+#[test]
+fn test_skip_time_range_exclusive_overnight() {
+    let skip = Skip::TimeRangeExclusive(time!(22:00:00), time!(06:00:00));
+    let night = OffsetDateTime::new_utc(date!(2024 - 12 - 25), time!(22:00:00));
+    let early_morning = OffsetDateTime::new_utc(date!(2024 - 12 - 25), time!(05:59:59));
+    let end_time = OffsetDateTime::new_utc(date!(2024 - 12 - 25), time!(06:00:00));
+
+    assert!(skip.is_skip(night));
+    assert!(skip.is_skip(early_morning));
+    assert!(!skip.is_skip(end_time));
+}
+
+#[test]
+fn test_skip_date_range_checked() {
+    let skip = Skip::date_range(date!(2024 - 12 - 24), date!(2024 - 12 - 26)).unwrap();
+    assert_eq!(
+        skip,
+        Skip::DateRange(date!(2024 - 12 - 24), date!(2024 - 12 - 26))
+    );
+
+    let single_day = Skip::date_range(date!(2024 - 12 - 25), date!(2024 - 12 - 25)).unwrap();
+    let christmas = OffsetDateTime::new_utc(date!(2024 - 12 - 25), time!(10:00:00));
+    assert!(single_day.is_skip(christmas));
+
+    assert!(Skip::date_range(date!(2024 - 12 - 26), date!(2024 - 12 - 24)).is_err());
+}
+
 #[test]
 fn test_skip_none() {
     let skip = Skip::None;
@@ -120,3 +181,211 @@ fn test_skip_display() {
     assert_eq!(format!("{day_skip}"), "day: [1, 2, 3]");
     assert_eq!(format!("{none_skip}"), "none");
 }
+
+#[test]
+fn test_skip_first_of_month() {
+    let skip = Skip::FirstOfMonth;
+    let first = OffsetDateTime::new_utc(date!(2024 - 01 - 01), time!(10:00:00));
+    let mid = OffsetDateTime::new_utc(date!(2024 - 01 - 15), time!(10:00:00));
+    let last = OffsetDateTime::new_utc(date!(2024 - 01 - 31), time!(10:00:00));
+
+    assert!(skip.is_skip(first));
+    assert!(!skip.is_skip(mid));
+    assert!(!skip.is_skip(last));
+    assert_eq!(format!("{skip}"), "first of month");
+}
+
+#[test]
+fn test_skip_last_of_month() {
+    let skip = Skip::LastOfMonth;
+    let first = OffsetDateTime::new_utc(date!(2024 - 01 - 01), time!(10:00:00));
+    let mid = OffsetDateTime::new_utc(date!(2024 - 01 - 15), time!(10:00:00));
+    let last = OffsetDateTime::new_utc(date!(2024 - 01 - 31), time!(10:00:00));
+
+    assert!(skip.is_skip(last));
+    assert!(!skip.is_skip(mid));
+    assert!(!skip.is_skip(first));
+    assert_eq!(format!("{skip}"), "last of month");
+}
+
+#[test]
+fn test_skip_last_of_month_february() {
+    let skip = Skip::LastOfMonth;
+    let leap_28 = OffsetDateTime::new_utc(date!(2024 - 02 - 28), time!(10:00:00));
+    let leap_29 = OffsetDateTime::new_utc(date!(2024 - 02 - 29), time!(10:00:00));
+    let common_28 = OffsetDateTime::new_utc(date!(2023 - 02 - 28), time!(10:00:00));
+
+    assert!(!skip.is_skip(leap_28));
+    assert!(skip.is_skip(leap_29));
+    assert!(skip.is_skip(common_28));
+}
+
+#[test]
+fn test_skip_time_grid() {
+    let skip = Skip::TimeGrid(time!(00:00), 30);
+    let at = |t| OffsetDateTime::new_utc(date!(2024 - 12 - 25), t);
+
+    assert!(skip.is_skip(at(time!(00:00:00))));
+    assert!(skip.is_skip(at(time!(09:30:00))));
+    assert!(skip.is_skip(at(time!(14:00:59))));
+    assert!(skip.is_skip(at(time!(23:30:00))));
+
+    assert!(!skip.is_skip(at(time!(09:15:00))));
+    assert!(!skip.is_skip(at(time!(09:45:00))));
+    assert!(!skip.is_skip(at(time!(09:31:00))));
+
+    // the anchor does not have to be the first slot of the day
+    let skip = Skip::TimeGrid(time!(10:15), 60);
+    assert!(skip.is_skip(at(time!(08:15:00))));
+    assert!(skip.is_skip(at(time!(10:15:00))));
+    assert!(!skip.is_skip(at(time!(10:45:00))));
+
+    let skip = Skip::TimeGrid(time!(12:00), 0);
+    assert!(skip.is_skip(at(time!(12:00:30))));
+    assert!(!skip.is_skip(at(time!(12:01:00))));
+    assert_eq!(skip.to_string(), "time grid: every 0m from 12:00:00.0");
+}
+
+#[test]
+fn test_skip_parse_expr() {
+    let at = |d, t| OffsetDateTime::new_utc(d, t);
+    let monday = date!(2024 - 12 - 23);
+    let saturday = date!(2024 - 12 - 21);
+    let christmas = date!(2024 - 12 - 25);
+
+    let skip = Skip::parse_expr("not weekday 1..5").unwrap();
+    assert_eq!(skip, Skip::Not(Box::new(Skip::DayRange(1, 5))));
+    assert!(skip.is_skip(at(saturday, time!(10:00))));
+    assert!(!skip.is_skip(at(monday, time!(10:00))));
+
+    let skip = Skip::parse_expr("weekday 6 or date 2024-12-25").unwrap();
+    assert_eq!(
+        skip,
+        Skip::Any(vec![Skip::Day(vec![6]), Skip::Date(christmas)])
+    );
+    assert!(skip.is_skip(at(saturday, time!(10:00))));
+    assert!(skip.is_skip(at(christmas, time!(10:00))));
+    assert!(!skip.is_skip(at(monday, time!(10:00))));
+    assert_eq!(skip.to_string(), "(day: [6] or date: 2024-12-25)");
+
+    // and binds tighter than or
+    let skip = Skip::parse_expr("weekday 6,7 or (date 2024-12-25) and time 09:00..12:00").unwrap();
+    assert_eq!(
+        skip,
+        Skip::Any(vec![
+            Skip::Day(vec![6, 7]),
+            Skip::All(vec![
+                Skip::Date(christmas),
+                Skip::TimeRange(time!(09:00), time!(12:00)),
+            ]),
+        ])
+    );
+    assert!(skip.is_skip(at(christmas, time!(10:00))));
+    assert!(!skip.is_skip(at(christmas, time!(13:00))));
+
+    let skip = Skip::parse_expr("not (weekday 6 or weekday 7)").unwrap();
+    assert!(skip.is_skip(at(monday, time!(10:00))));
+    assert!(!skip.is_skip(at(saturday, time!(10:00))));
+
+    assert!(Skip::parse_expr("").is_err());
+    assert!(Skip::parse_expr("weekday 6 or").is_err());
+    assert!(Skip::parse_expr("(weekday 6").is_err());
+    assert!(Skip::parse_expr("weekday 6)").is_err());
+    assert!(Skip::parse_expr("weekday 0..3").is_err());
+    assert_eq!(
+        Skip::parse_expr("weekday 6..1").unwrap(),
+        Skip::DayRange(6, 1)
+    );
+    assert!(Skip::parse_expr("holiday").is_err());
+}
+
+#[test]
+fn test_skip_only_day() {
+    let skip = Skip::OnlyDay(vec![1, 2, 3, 4, 5]);
+    let monday = OffsetDateTime::new_utc(date!(2024 - 12 - 23), time!(10:00:00));
+    let friday = OffsetDateTime::new_utc(date!(2024 - 12 - 27), time!(10:00:00));
+    let saturday = OffsetDateTime::new_utc(date!(2024 - 12 - 21), time!(10:00:00));
+
+    assert!(!skip.is_skip(monday));
+    assert!(!skip.is_skip(friday));
+    assert!(skip.is_skip(saturday));
+    assert_eq!(skip.to_string(), "only day: [1, 2, 3, 4, 5]");
+}
+
+#[test]
+fn test_skip_only_time_range() {
+    let at = |t| OffsetDateTime::new_utc(date!(2024 - 12 - 25), t);
+
+    let business_hours = Skip::OnlyTimeRange(time!(09:00), time!(17:00));
+    assert!(!business_hours.is_skip(at(time!(09:00))));
+    assert!(!business_hours.is_skip(at(time!(12:00))));
+    assert!(!business_hours.is_skip(at(time!(17:00))));
+    assert!(business_hours.is_skip(at(time!(08:59))));
+    assert!(business_hours.is_skip(at(time!(18:00))));
+
+    let night = Skip::OnlyTimeRange(time!(22:00), time!(06:00));
+    assert!(!night.is_skip(at(time!(23:00))));
+    assert!(!night.is_skip(at(time!(05:00))));
+    assert!(night.is_skip(at(time!(12:00))));
+    assert!(night.is_skip(at(time!(21:59))));
+}
+
+#[test]
+fn test_skip_month() {
+    let on = |d| OffsetDateTime::new_utc(d, time!(10:00:00));
+
+    let summer = Skip::Month(vec![7, 8]);
+    assert!(summer.is_skip(on(date!(2024 - 07 - 01))));
+    assert!(summer.is_skip(on(date!(2024 - 08 - 31))));
+    assert!(!summer.is_skip(on(date!(2024 - 09 - 01))));
+    assert_eq!(summer.to_string(), "month: [7, 8]");
+
+    let spring = Skip::MonthRange(3, 5);
+    assert!(spring.is_skip(on(date!(2024 - 03 - 01))));
+    assert!(spring.is_skip(on(date!(2024 - 05 - 31))));
+    assert!(!spring.is_skip(on(date!(2024 - 06 - 01))));
+    assert!(!spring.is_skip(on(date!(2024 - 02 - 29))));
+
+    // wraps over the year
+    let winter = Skip::MonthRange(11, 2);
+    assert!(winter.is_skip(on(date!(2024 - 11 - 01))));
+    assert!(winter.is_skip(on(date!(2024 - 12 - 25))));
+    assert!(winter.is_skip(on(date!(2025 - 01 - 15))));
+    assert!(winter.is_skip(on(date!(2025 - 02 - 28))));
+    assert!(!winter.is_skip(on(date!(2025 - 03 - 01))));
+    assert!(!winter.is_skip(on(date!(2024 - 10 - 31))));
+}
+
+#[test]
+fn test_skip_nth_weekday() {
+    let on = |d| OffsetDateTime::new_utc(d, time!(10:00:00));
+
+    // December 2024 starts on a Sunday
+    let first_monday = Skip::NthWeekday(1, 1);
+    assert!(!first_monday.is_skip(on(date!(2024 - 12 - 02))));
+    assert!(first_monday.is_skip(on(date!(2024 - 12 - 09))));
+    assert!(first_monday.is_skip(on(date!(2024 - 12 - 01))));
+    assert!(first_monday.is_skip(on(date!(2024 - 12 - 03))));
+
+    let second_tuesday = Skip::NthWeekday(2, 2);
+    assert!(!second_tuesday.is_skip(on(date!(2024 - 12 - 10))));
+    assert!(second_tuesday.is_skip(on(date!(2024 - 12 - 03))));
+
+    let last_friday = Skip::NthWeekday(-1, 5);
+    assert!(!last_friday.is_skip(on(date!(2024 - 12 - 27))));
+    assert!(last_friday.is_skip(on(date!(2024 - 12 - 20))));
+    // February 2025 ends on a Friday
+    assert!(!last_friday.is_skip(on(date!(2025 - 02 - 28))));
+    assert!(last_friday.is_skip(on(date!(2025 - 02 - 21))));
+
+    let second_to_last_friday = Skip::NthWeekday(-2, 5);
+    assert!(!second_to_last_friday.is_skip(on(date!(2024 - 12 - 20))));
+
+    // there is no fifth Monday in February 2025
+    let fifth_monday = Skip::NthWeekday(5, 1);
+    assert!(fifth_monday.is_skip(on(date!(2025 - 02 - 24))));
+    assert!(!fifth_monday.is_skip(on(date!(2024 - 12 - 30))));
+    assert!(Skip::NthWeekday(0, 1).is_skip(on(date!(2024 - 12 - 02))));
+
+    assert_eq!(last_friday.to_string(), "nth weekday: 5 in week -1");
+}
@@ -1,4 +1,5 @@
-use easy_schedule::Task;
+use easy_schedule::{Skip, Task};
+use time::macros::{date, time};
 
 #[test]
 fn test_basic_tasks_without_skip() {
@@ -65,6 +66,75 @@ fn test_multiple_skip_conditions() {
     }
 }
 
+#[test]
+fn test_month_boundary_skips() {
+    let task = Task::parse("interval(60, [firstofmonth, lastofmonth])").unwrap();
+    if let Task::Interval(60, Some(skips)) = task {
+        assert_eq!(skips.len(), 2);
+        assert_eq!(skips[0], easy_schedule::Skip::FirstOfMonth);
+        assert_eq!(skips[1], easy_schedule::Skip::LastOfMonth);
+    } else {
+        panic!("Expected Interval task with month boundary skips");
+    }
+
+    assert!(Task::parse("wait(10, firstofmonth 1)").is_err());
+}
+
+#[test]
+fn test_date_range_skip() {
+    let task = Task::parse("interval(60, date 2024-12-24..2024-12-26)").unwrap();
+    if let Task::Interval(60, Some(skips)) = task {
+        assert_eq!(
+            skips,
+            vec![easy_schedule::Skip::DateRange(
+                date!(2024 - 12 - 24),
+                date!(2024 - 12 - 26)
+            )]
+        );
+    } else {
+        panic!("Expected Interval task with date range skip");
+    }
+
+    let err = Task::parse("interval(60, date 2024-12-26..2024-12-24)").unwrap_err();
+    assert!(err.contains("after end"), "{err}");
+}
+
+#[test]
+fn test_weekday_lists() {
+    let task = Task::parse("wait(10, weekday 6,7)").unwrap();
+    assert_eq!(task, Task::Wait(10, Some(vec![Skip::Day(vec![6, 7])])));
+
+    let task = Task::parse("wait(10, weekday 1-5)").unwrap();
+    assert_eq!(task, Task::Wait(10, Some(vec![Skip::DayRange(1, 5)])));
+
+    // a list keeps the weekdays of a token together
+    let task = Task::parse("wait(10, [weekday 6,7, time 12:00..13:00])").unwrap();
+    assert_eq!(
+        task,
+        Task::Wait(
+            10,
+            Some(vec![
+                Skip::Day(vec![6, 7]),
+                Skip::TimeRange(time!(12:00), time!(13:00)),
+            ])
+        )
+    );
+
+    let task = Task::parse("wait(10, weekday 6)").unwrap();
+    assert_eq!(task, Task::Wait(10, Some(vec![Skip::Day(vec![6])])));
+
+    for invalid in [
+        "weekday 0",
+        "weekday 8",
+        "weekday 6,8",
+        "weekday 0-5",
+        "weekday 1-",
+    ] {
+        let err = Task::parse(&format!("wait(10, {invalid})")).unwrap_err();
+        assert!(err.contains("eekday"), "{invalid}: {err}");
+    }
+}
+
 #[test]
 fn test_error_cases() {
     // Invalid weekday
@@ -98,3 +168,150 @@ fn test_parsing_display() {
         assert!(!display.is_empty());
     }
 }
+
+#[test]
+fn test_only_skip_parsing() {
+    use easy_schedule::Skip;
+    use time::macros::time;
+
+    let task = Task::parse("interval(60, [only_weekday 1..5, only_time 09:00..17:00])").unwrap();
+    assert_eq!(
+        task,
+        Task::Interval(
+            60,
+            Some(vec![
+                Skip::OnlyDay(vec![1, 2, 3, 4, 5]),
+                Skip::OnlyTimeRange(time!(09:00), time!(17:00)),
+            ])
+        )
+    );
+
+    let task = Task::parse("at(09:00, only_weekday 6)").unwrap();
+    assert_eq!(
+        task,
+        Task::At(time!(09:00), Some(vec![Skip::OnlyDay(vec![6])]))
+    );
+
+    let task = Task::parse("at(09:00, only_weekday 6..1)").unwrap();
+    assert_eq!(
+        task,
+        Task::At(time!(09:00), Some(vec![Skip::OnlyDay(vec![6, 7, 1])]))
+    );
+
+    assert!(Task::parse("at(09:00, only_time 12:00)").is_err());
+    assert!(Task::parse("at(09:00, only_weekday 8)").is_err());
+    assert!(Task::parse("at(09:00, only_weekday)").is_err());
+}
+
+#[test]
+fn test_skip_mode_parsing() {
+    use easy_schedule::Skip;
+    use time::macros::time;
+
+    let weekend_evening = vec![
+        Skip::Day(vec![6]),
+        Skip::TimeRange(time!(18:00), time!(23:59)),
+    ];
+
+    let task = Task::parse("interval(30, all[weekday 6, time 18:00..23:59])").unwrap();
+    assert_eq!(
+        task,
+        Task::Interval(30, Some(vec![Skip::All(weekend_evening.clone())]))
+    );
+
+    let task = Task::parse("interval(30, any[weekday 6, time 18:00..23:59])").unwrap();
+    assert_eq!(task, Task::Interval(30, Some(weekend_evening.clone())));
+
+    // a plain list skips when any condition matches
+    let task = Task::parse("interval(30, [weekday 6, time 18:00..23:59])").unwrap();
+    assert_eq!(task, Task::Interval(30, Some(weekend_evening)));
+
+    assert!(Task::parse("interval(30, all[weekday 6, holiday])").is_err());
+}
+
+#[test]
+fn test_skip_group_parsing() {
+    use time::macros::datetime;
+
+    // skips on weekend evenings only
+    let weekend_evening = Skip::All(vec![
+        Skip::Day(vec![6, 7]),
+        Skip::TimeRange(time!(18:00), time!(23:59)),
+    ]);
+    // 2024-12-28 is a Saturday
+    assert!(weekend_evening.is_skip(datetime!(2024-12-28 19:00 +8)));
+    assert!(!weekend_evening.is_skip(datetime!(2024-12-28 09:00 +8)));
+    assert!(!weekend_evening.is_skip(datetime!(2024-12-27 19:00 +8)));
+
+    let weekend_evening = Skip::All(vec![
+        Skip::DayRange(6, 7),
+        Skip::TimeRange(time!(18:00), time!(23:59)),
+    ]);
+    let task = Task::parse("interval(30, all(weekday 6..7, time 18:00..23:59))").unwrap();
+    assert_eq!(
+        task,
+        Task::Interval(30, Some(vec![weekend_evening.clone()]))
+    );
+
+    // groups nest, an inner `any` keeps its own group
+    let task =
+        Task::parse("interval(30, any(date 2024-12-25, all[weekday 6..7, time 18:00..23:59]))")
+            .unwrap();
+    assert_eq!(
+        task,
+        Task::Interval(
+            30,
+            Some(vec![Skip::Date(date!(2024 - 12 - 25)), weekend_evening])
+        )
+    );
+    let task =
+        Task::parse("interval(30, all(weekday 6..7, any(time 06:00..08:00, time 18:00..23:59)))")
+            .unwrap();
+    assert_eq!(
+        task,
+        Task::Interval(
+            30,
+            Some(vec![Skip::All(vec![
+                Skip::DayRange(6, 7),
+                Skip::Any(vec![
+                    Skip::TimeRange(time!(06:00), time!(08:00)),
+                    Skip::TimeRange(time!(18:00), time!(23:59)),
+                ]),
+            ])])
+        )
+    );
+
+    assert!(Task::parse("interval(30, all(weekday 6, holiday))").is_err());
+}
+
+#[test]
+fn test_skip_parse_month() {
+    let task = Task::parse("at(09:00, month 7)").unwrap();
+    assert_eq!(
+        task,
+        Task::At(time!(09:00), Some(vec![Skip::Month(vec![7])]))
+    );
+    let task = Task::parse("at(09:00, [month 11..2, weekday 6])").unwrap();
+    assert_eq!(
+        task,
+        Task::At(
+            time!(09:00),
+            Some(vec![Skip::MonthRange(11, 2), Skip::Day(vec![6])])
+        )
+    );
+    assert_eq!(
+        Skip::parse_expr("month 7,8 and not weekday 1").unwrap(),
+        Skip::All(vec![
+            Skip::Month(vec![7, 8]),
+            Skip::Not(Box::new(Skip::Day(vec![1])))
+        ])
+    );
+    assert_eq!(
+        task.to_config_string().unwrap(),
+        "at(09:00:00, [month 11..2, weekday 6])"
+    );
+
+    assert!(Task::parse("at(09:00, month 13)").is_err());
+    assert!(Task::parse("at(09:00, month 0..2)").is_err());
+    assert!(Task::parse("at(09:00, month july)").is_err());
+}
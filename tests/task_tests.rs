@@ -1,7 +1,7 @@
 use easy_schedule::{prelude::*, task};
 use time::{
     OffsetDateTime,
-    macros::{offset, time},
+    macros::{date, datetime, offset, time},
 };
 
 #[test]
@@ -39,6 +39,71 @@ fn test_task_from_string_once() {
     }
 }
 
+#[test]
+fn test_task_parse_once_without_offset() {
+    let with_offset = Task::parse("once(2024-12-31 23:59:59 +02)").unwrap();
+    assert_eq!(
+        with_offset,
+        Task::Once(datetime!(2024-12-31 23:59:59 +2), None)
+    );
+
+    // the default scheduler timezone
+    let task = Task::parse("once(2024-12-31 23:59:59)").unwrap();
+    assert_eq!(task, Task::Once(datetime!(2024-12-31 23:59:59 +8), None));
+
+    let task =
+        Task::parse_with_timezone_minutes("once(2024-12-31 23:59:59, weekday 6)", 330).unwrap();
+    assert_eq!(
+        task,
+        Task::Once(
+            datetime!(2024-12-31 23:59:59 +5:30),
+            Some(vec![Skip::Day(vec![6])])
+        )
+    );
+    // an explicit offset wins over the scheduler timezone
+    let task = Task::parse_with_timezone_minutes("once(2024-12-31 23:59:59 +02)", 330).unwrap();
+    assert_eq!(task, with_offset);
+
+    assert!(Task::parse("once(2024-12-31)").is_err());
+}
+
+#[test]
+fn test_task_parse_once_on() {
+    let task = Task::parse("onceon(2024-12-31 at 09:00)").unwrap();
+    assert_eq!(
+        task,
+        Task::OnceOn(date!(2024 - 12 - 31), time!(09:00), None)
+    );
+    let task = Task::parse("onceon(2024-12-31 at 09:00:30, weekday 2)").unwrap();
+    assert_eq!(
+        task,
+        Task::OnceOn(
+            date!(2024 - 12 - 31),
+            time!(09:00:30),
+            Some(vec![Skip::Day(vec![2])])
+        )
+    );
+
+    // fires at the date and time in the offset of `now`, the scheduler timezone
+    let task = Task::OnceOn(date!(2024 - 12 - 31), time!(09:00), None);
+    let times: Vec<_> = task
+        .run_times(datetime!(2024-12-30 12:00:00 +5:30))
+        .collect();
+    assert_eq!(times, vec![datetime!(2024-12-31 09:00:00 +5:30)]);
+    assert_eq!(
+        task.run_times(datetime!(2024-12-31 09:00:00 +8)).next(),
+        None
+    );
+    assert_eq!(
+        task.previous_run_time(datetime!(2025-01-01 00:00:00 +8)),
+        Some(datetime!(2024-12-31 09:00:00 +8))
+    );
+
+    assert!(Task::parse("onceon(2024-12-31)").is_err());
+    assert!(Task::parse("onceon(2024-12-31 at 25:00)").is_err());
+    assert!(Task::parse("onceon(2024-13-01 at 09:00)").is_err());
+}
+
 #[test]
 fn test_task_parse_success() {
     let task = Task::parse("wait(10)").unwrap();
@@ -120,9 +185,10 @@ fn test_task_display() {
     let at_task = Task::At(time!(14:30:00), None);
     let once_task = Task::Once(OffsetDateTime::now_utc().to_offset(offset!(+8)), None);
 
-    assert!(format!("{wait_task}").starts_with("wait: 10"));
-    assert!(format!("{interval_task}").starts_with("interval: 30"));
-    assert!(format!("{at_task}").starts_with("at: 14:30:00"));
+    assert_eq!(format!("{wait_task}"), "wait(10)");
+    assert_eq!(format!("{interval_task}"), "interval(30)");
+    assert_eq!(format!("{at_task}"), "at(14:30:00)");
+    // a fraction of a second has no syntax, so it is described
     assert!(format!("{once_task}").starts_with("once:"));
 }
 
@@ -132,9 +198,7 @@ fn test_task_display_with_skip() {
     let wait_task = Task::Wait(10, skip);
 
     let display = format!("{wait_task}");
-    assert!(display.contains("wait: 10"));
-    assert!(display.contains("day: [1, 2]"));
-    assert!(display.contains("time: 12:00:00"));
+    assert_eq!(display, "wait(10, [weekday 1,2, time 12:00])");
 }
 
 #[test]
@@ -230,3 +294,1031 @@ fn test_task_macro_multiple_skips() {
         )
     );
 }
+
+#[test]
+fn test_task_macro_matches_parse() {
+    let cases = [
+        (task!(at 14:30:45), "at(14:30:45)"),
+        (
+            task!(at 14:30:45, daterange 2024-12-24..2024-12-26),
+            "at(14:30:45, date 2024-12-24..2024-12-26)",
+        ),
+        (task!(wait 10, dayrange 1..5), "wait(10, weekday 1..5)"),
+        (
+            task!(interval 60, [dayrange 6..7, daterange 2024-12-24..2024-12-31, weekday 3]),
+            "interval(60, [weekday 6..7, date 2024-12-24..2024-12-31, weekday 3])",
+        ),
+        (task!(cron "0 9 * * *"), "cron(0 9 * * *)"),
+        (
+            task!(cron "0 9 * * *", dayrange 6..7),
+            "cron(0 9 * * *, weekday 6..7)",
+        ),
+        (
+            task!(cron "*/10 * * * * *", [weekday 1, time 12:00..13:00]),
+            "cron(*/10 * * * * *, [weekday 1, time 12:00..13:00])",
+        ),
+    ];
+    for (task, config) in cases {
+        assert_eq!(task, Task::parse(config).unwrap(), "{config}");
+    }
+}
+
+#[test]
+fn test_task_parse_aliases() {
+    assert_eq!(Task::parse("@minutely").unwrap(), Task::Interval(60, None));
+    assert_eq!(Task::parse("@hourly").unwrap(), Task::Hourly(0, None));
+    assert_eq!(Task::parse("@daily").unwrap(), Task::At(time!(00:00), None));
+    assert_eq!(
+        Task::parse(" @weekly ").unwrap(),
+        Task::Weekly(vec![1], time!(00:00), None)
+    );
+
+    let result = Task::parse("@yearly");
+    assert!(result.unwrap_err().contains("Unknown schedule alias"));
+}
+
+#[test]
+fn test_task_parse_hourly() {
+    assert_eq!(Task::parse("hourly(15)").unwrap(), Task::Hourly(15, None));
+    assert!(Task::parse("hourly(60)").is_err());
+}
+
+#[test]
+fn test_hourly_and_weekly_run_times() {
+    let now = OffsetDateTime::new_in_offset(
+        time::macros::date!(2024 - 12 - 25),
+        time!(10:20:30),
+        offset!(+8),
+    );
+
+    let hourly = Task::Hourly(15, None);
+    let times = hourly.run_times(now).take(2).collect::<Vec<_>>();
+    assert_eq!(times[0].time(), time!(11:15:00));
+    assert_eq!(times[1].time(), time!(12:15:00));
+
+    // 2024-12-25 is a Wednesday
+    let weekly = Task::Weekly(vec![1, 5], time!(09:00), None);
+    let times = weekly.run_times(now).take(3).collect::<Vec<_>>();
+    assert_eq!(times[0].date(), time::macros::date!(2024 - 12 - 27));
+    assert_eq!(times[1].date(), time::macros::date!(2024 - 12 - 30));
+    assert_eq!(times[2].date(), time::macros::date!(2025 - 01 - 03));
+    assert!(times.iter().all(|time| time.time() == time!(09:00)));
+}
+
+#[test]
+fn test_task_parse_at_until() {
+    let task = Task::parse("at(09:00 until 2025-01-01)").unwrap();
+    assert_eq!(
+        task,
+        Task::AtUntil(time!(09:00), date!(2025 - 01 - 01), None)
+    );
+
+    let task = Task::parse("at(09:00 until 2025-01-01, weekday 7)").unwrap();
+    assert_eq!(
+        task,
+        Task::AtUntil(
+            time!(09:00),
+            date!(2025 - 01 - 01),
+            Some(vec![Skip::Day(vec![7])])
+        )
+    );
+    assert_eq!(task.to_string(), "at(09:00:00 until 2025-01-01, weekday 7)");
+
+    // existing forms keep working
+    assert_eq!(
+        Task::parse("at(09:00)").unwrap(),
+        Task::At(time!(09:00), None)
+    );
+
+    assert!(Task::parse("at(09:00 until)").is_err());
+    assert!(Task::parse("at(09:00 until 2025-13-01)").is_err());
+    assert!(Task::parse("at(09:00 until 2025-01-01 until 2025-02-01)").is_err());
+    assert!(Task::parse("wait(10 until 2025-01-01)").is_err());
+}
+
+#[test]
+fn test_at_until_run_times() {
+    let task = Task::AtUntil(time!(09:00), date!(2024 - 12 - 27), None);
+    let now = datetime!(2024-12-25 10:00 +8);
+    let runs = task.run_times(now).collect::<Vec<_>>();
+    assert_eq!(
+        runs,
+        vec![
+            datetime!(2024-12-26 09:00 +8),
+            datetime!(2024-12-27 09:00 +8)
+        ]
+    );
+
+    let expired = datetime!(2024-12-27 10:00 +8);
+    assert_eq!(task.run_times(expired).next(), None);
+}
+
+#[test]
+fn test_business_day_interval_run_times() {
+    // 2024-12-26 is a Thursday
+    let thursday = datetime!(2024-12-26 10:00 +8);
+
+    let task = Task::BusinessDayInterval(1, time!(09:00), None);
+    let runs = task.run_times(thursday).take(3).collect::<Vec<_>>();
+    assert_eq!(
+        runs,
+        vec![
+            datetime!(2024-12-27 09:00 +8),
+            datetime!(2024-12-30 09:00 +8),
+            datetime!(2024-12-31 09:00 +8),
+        ]
+    );
+
+    // holidays do not count as business days
+    let holiday = Some(vec![Skip::Date(date!(2024 - 12 - 30))]);
+    let task = Task::BusinessDayInterval(1, time!(09:00), holiday);
+    let runs = task.run_times(thursday).take(2).collect::<Vec<_>>();
+    assert_eq!(
+        runs,
+        vec![
+            datetime!(2024-12-27 09:00 +8),
+            datetime!(2024-12-31 09:00 +8)
+        ]
+    );
+
+    let task = Task::BusinessDayInterval(3, time!(09:00), None);
+    let runs = task.run_times(thursday).take(2).collect::<Vec<_>>();
+    assert_eq!(
+        runs,
+        vec![
+            datetime!(2024-12-27 09:00 +8),
+            datetime!(2025-01-01 09:00 +8)
+        ]
+    );
+}
+
+#[test]
+fn test_humanize_next() {
+    let now = datetime!(2024-12-26 10:00 +8);
+
+    assert_eq!(Task::Wait(45, None).humanize_next(now), "in 45s");
+    assert_eq!(Task::Interval(1200, None).humanize_next(now), "in 20m");
+    assert_eq!(Task::At(time!(13:20), None).humanize_next(now), "in 3h 20m");
+    assert_eq!(
+        Task::At(time!(09:00), None).humanize_next(now),
+        "tomorrow at 09:00"
+    );
+    assert_eq!(
+        Task::Weekly(vec![1], time!(09:00), None).humanize_next(now),
+        "in 4 days at 09:00"
+    );
+
+    let past = Task::Once(datetime!(2024-12-25 10:00 +8), None);
+    assert_eq!(past.humanize_next(now), "never");
+
+    // skipped runs are not announced
+    let skip = Some(vec![Skip::Date(date!(2024 - 12 - 27))]);
+    assert_eq!(
+        Task::At(time!(09:00), skip).humanize_next(now),
+        "in 2 days at 09:00"
+    );
+}
+
+#[test]
+fn test_task_parse_cron() {
+    let task = Task::parse("cron(0 30 9 * * 1-5)").unwrap();
+    assert_eq!(task, Task::Cron("0 30 9 * * 1-5".to_string(), None));
+
+    let task = Task::parse("cron(30 9,18 * * 1-5, [date 2024-12-25])").unwrap();
+    assert_eq!(
+        task,
+        Task::Cron(
+            "30 9,18 * * 1-5".to_string(),
+            Some(vec![Skip::Date(time::macros::date!(2024 - 12 - 25))])
+        )
+    );
+
+    let err = Task::parse("cron(30 9 * *)").unwrap_err();
+    assert!(err.contains("5 or 6 fields"), "{err}");
+    let err = Task::parse("cron(0 30 25 * * *)").unwrap_err();
+    assert!(err.contains("Invalid hour '25'"), "{err}");
+    assert!(Task::parse("cron(*/0 * * * *)").is_err());
+    assert!(Task::parse("cron(0 5-1 * * *)").is_err());
+}
+
+#[test]
+fn test_cron_run_times() {
+    let now = datetime!(2024-12-26 10:00 +8);
+
+    let daily = Task::Cron("30 9 * * *".to_string(), None);
+    let runs = daily.run_times(now).take(2).collect::<Vec<_>>();
+    assert_eq!(
+        runs,
+        vec![
+            datetime!(2024-12-27 09:30 +8),
+            datetime!(2024-12-28 09:30 +8)
+        ]
+    );
+
+    // every weekday at 9:30 and 18:00, from a Thursday
+    let weekdays = Task::Cron("0 30,0 9,18 * * 1-5".to_string(), None);
+    let runs = weekdays
+        .run_times(now)
+        .filter(|run| matches!((run.hour(), run.minute()), (9, 30) | (18, 0)))
+        .take(3)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        runs,
+        vec![
+            datetime!(2024-12-26 18:00 +8),
+            datetime!(2024-12-27 09:30 +8),
+            datetime!(2024-12-27 18:00 +8),
+        ]
+    );
+
+    // both day fields restricted: the 1st of the month or any Sunday
+    let either = Task::Cron("0 0 1 * 0".to_string(), None);
+    let runs = either.run_times(now).take(3).collect::<Vec<_>>();
+    assert_eq!(
+        runs,
+        vec![
+            datetime!(2024-12-29 00:00 +8),
+            datetime!(2025-01-01 00:00 +8),
+            datetime!(2025-01-05 00:00 +8),
+        ]
+    );
+
+    let never = Task::Cron("0 0 30 2 *".to_string(), None);
+    assert_eq!(never.run_times(now).next(), None);
+}
+
+#[test]
+fn test_run_times_past_the_representable_range() {
+    let now = datetime!(2024-12-26 10:00:00 +8);
+    // about 3170 years a step, the runs end before year 9999 instead of overflowing
+    let times: Vec<_> = Task::Interval(100_000_000_000, None)
+        .run_times(now)
+        .collect();
+    assert_eq!(times.len(), 2);
+    assert!(times[0] > now && times[1] > times[0]);
+    for task in [
+        Task::Wait(u64::MAX, None),
+        Task::Interval(u64::MAX, None),
+        Task::IntervalMillis(u64::MAX, None),
+        Task::IntervalPhased(u64::MAX, now, None),
+    ] {
+        assert_eq!(task.run_times(now).next(), None, "{task:?}");
+    }
+}
+
+#[test]
+fn test_interval_phased_run_times() {
+    let anchor = datetime!(2024-01-01 00:00:00 +8);
+    let task = Task::IntervalPhased(300, anchor, None);
+
+    // different start times land on the same 5 minute grid
+    for now in [
+        datetime!(2024-12-26 10:00:00.5 +8),
+        datetime!(2024-12-26 10:01:30 +8),
+        datetime!(2024-12-26 10:04:59.5 +8),
+    ] {
+        let runs = task.run_times(now).take(2).collect::<Vec<_>>();
+        assert_eq!(
+            runs,
+            vec![
+                datetime!(2024-12-26 10:05:00 +8),
+                datetime!(2024-12-26 10:10:00 +8)
+            ],
+            "from {now}"
+        );
+    }
+
+    // before the anchor the grid extends backwards
+    let early = datetime!(2023-12-31 23:52:00 +8);
+    assert_eq!(
+        task.run_times(early).next(),
+        Some(datetime!(2023-12-31 23:55:00 +8))
+    );
+
+    // a start exactly on the grid fires right away
+    let on_grid = datetime!(2024-12-26 10:05:00 +8);
+    assert_eq!(task.run_times(on_grid).next(), Some(on_grid));
+}
+
+#[test]
+fn test_task_from_str() {
+    let task: Task = "wait(10)".parse().unwrap();
+    assert_eq!(task, Task::Wait(10, None));
+
+    let err = "wait(abc)".parse::<Task>().unwrap_err();
+    assert!(err.contains("Invalid seconds value 'abc'"), "{err}");
+}
+
+#[test]
+fn test_task_parse_human_durations() {
+    assert_eq!(Task::parse("interval(1h)"), Ok(Task::Interval(3600, None)));
+    assert_eq!(Task::parse("wait(90s)"), Ok(Task::Wait(90, None)));
+    assert_eq!(
+        Task::parse("interval(1h30m)"),
+        Ok(Task::Interval(5400, None))
+    );
+    assert_eq!(Task::parse("wait(1d 2h)"), Ok(Task::Wait(93600, None)));
+    assert_eq!(
+        Task::parse("wait(1m500ms)"),
+        Ok(Task::WaitMillis(60500, None))
+    );
+    assert_eq!(
+        Task::parse("interval(2m, weekday 7)"),
+        Ok(Task::Interval(120, Some(vec![Skip::Day(vec![7])])))
+    );
+    // bare integers are still seconds
+    assert_eq!(
+        Task::parse("interval(3600)"),
+        Ok(Task::Interval(3600, None))
+    );
+
+    let err = Task::parse("wait(5x)").unwrap_err();
+    assert!(err.contains("Invalid seconds value '5x'"), "{err}");
+    assert!(Task::parse("interval(h)").is_err());
+    assert!(Task::parse("interval(1h30)").is_err());
+}
+
+#[test]
+fn test_task_parse_many() {
+    let config = "
+        # nightly backup
+        at(02:00, weekday 7)
+
+        # polling
+        interval(60)
+          # indented comment
+        wait(5)
+    ";
+    let tasks = Task::parse_many(config).unwrap();
+    assert_eq!(
+        tasks,
+        vec![
+            Task::At(time!(02:00), Some(vec![Skip::Day(vec![7])])),
+            Task::Interval(60, None),
+            Task::Wait(5, None),
+        ]
+    );
+
+    assert_eq!(Task::parse_many("\n# only comments\n").unwrap(), vec![]);
+
+    let err = Task::parse_many("wait(5)\n\n# next is broken\ninterval(abc)").unwrap_err();
+    assert!(err.starts_with("line 4: "), "{err}");
+}
+
+#[test]
+fn test_task_parse_seconds_unit() {
+    assert_eq!(Task::parse("wait(10s)"), Task::parse("wait(10)"));
+    assert_eq!(
+        Task::parse("interval(30s, weekday 6)"),
+        Task::parse("interval(30, weekday 6)")
+    );
+    assert_eq!(
+        Task::parse("fixed_rate(5s)").unwrap(),
+        Task::FixedRate(5, None)
+    );
+
+    assert_eq!(
+        Task::parse_strict("wait(10s, weekday 6)").unwrap(),
+        Task::Wait(10, Some(vec![Skip::Day(vec![6])]))
+    );
+    assert_eq!(
+        Task::parse_strict("interval(250ms)").unwrap(),
+        Task::IntervalMillis(250, None)
+    );
+    assert_eq!(
+        Task::parse_strict("at(09:00)").unwrap(),
+        Task::At(time!(09:00), None)
+    );
+    let err = Task::parse_strict("wait(10)").unwrap_err();
+    assert!(err.contains("Missing unit"), "{err}");
+    assert!(err.contains("'10ms'"), "{err}");
+    assert!(Task::parse_strict("interval(1.5, weekday 6)").is_err());
+    let err = Task::parse_strict("fixed_rate(5)").unwrap_err();
+    assert!(err.ends_with("Expected '5s'"), "{err}");
+}
+
+#[test]
+fn test_task_parse_whitespace_and_comments() {
+    let spaced = [
+        ("wait( 10 , weekday 6 )", "wait(10, weekday 6)"),
+        ("wait (10)", "wait(10)"),
+        (
+            "  interval(\t30 ,\tweekday  6 )  ",
+            "interval(30, weekday 6)",
+        ),
+        (
+            "interval(60,[ weekday 6 ,  weekday 7 ])",
+            "interval(60, [weekday 6, weekday 7])",
+        ),
+        (
+            "at( 09:00 , 13:00 ,  weekday 6 )",
+            "at(09:00,13:00, weekday 6)",
+        ),
+        ("at(09:00 until  2025-01-01)", "at(09:00 until 2025-01-01)"),
+        (
+            "cron(  0 9,18 * * *  ,  weekday 6 )",
+            "cron(0 9,18 * * *, weekday 6)",
+        ),
+        ("once( 2024-12-31  23:59:59 )", "once(2024-12-31 23:59:59)"),
+        ("wait(10) # ten seconds", "wait(10)"),
+        (
+            "interval(30, weekday 6)# weekends",
+            "interval(30, weekday 6)",
+        ),
+        ("wait(10) # see (docs)", "wait(10)"),
+        ("@daily # midnight", "@daily"),
+    ];
+    for (input, canonical) in spaced {
+        assert_eq!(
+            Task::parse(input),
+            Ok(Task::parse(canonical).unwrap()),
+            "{input}"
+        );
+    }
+
+    for malformed in [
+        "wait(10",
+        "wait 10",
+        "wait( )",
+        "wait(1 0)",
+        "wait(10, )",
+        "# wait(10)",
+        "wait(10, weekday) # comment",
+        "interval(x) # comment",
+        "wait(10) weekday 6",
+        "wait(10))",
+    ] {
+        assert!(Task::parse(malformed).is_err(), "{malformed}");
+    }
+}
+
+#[test]
+fn test_task_parse_sub_second() {
+    assert_eq!(
+        Task::parse("interval(250ms)").unwrap(),
+        Task::IntervalMillis(250, None)
+    );
+    assert_eq!(
+        Task::parse("wait(1.5s)").unwrap(),
+        Task::WaitMillis(1500, None)
+    );
+    assert_eq!(
+        Task::parse("wait(0.05)").unwrap(),
+        Task::WaitMillis(50, None)
+    );
+    // whole seconds keep the second based variants
+    assert_eq!(
+        Task::parse("interval(2s)").unwrap(),
+        Task::Interval(2, None)
+    );
+
+    assert!(Task::parse("wait(1.2345s)").is_err());
+    assert!(Task::parse("wait(1.s)").is_err());
+    assert!(Task::parse("interval(-5ms)").is_err());
+
+    let task = Task::IntervalMillis(200, None);
+    let now = datetime!(2024-12-26 10:00:00 +8);
+    let times: Vec<_> = task.run_times(now).take(3).collect();
+    assert_eq!(
+        times,
+        vec![
+            datetime!(2024-12-26 10:00:00.2 +8),
+            datetime!(2024-12-26 10:00:00.4 +8),
+            datetime!(2024-12-26 10:00:00.6 +8),
+        ]
+    );
+    assert_eq!(task.to_string(), "interval(200ms)");
+}
+
+#[test]
+fn test_task_parse_fixed_rate() {
+    assert_eq!(
+        Task::parse("fixed_rate(5)").unwrap(),
+        Task::FixedRate(5, None)
+    );
+    assert!(Task::parse("fixed_rate(1.5s)").is_err());
+
+    let task = Task::FixedRate(5, None);
+    let now = datetime!(2024-12-26 10:00:00 +8);
+    let times: Vec<_> = task.run_times(now).take(2).collect();
+    assert_eq!(
+        times,
+        vec![
+            datetime!(2024-12-26 10:00:05 +8),
+            datetime!(2024-12-26 10:00:10 +8)
+        ]
+    );
+}
+
+#[test]
+fn test_task_previous_run_time() {
+    let task = Task::At(time!(09:00), None);
+    let now = datetime!(2024-12-26 10:00:00 +8);
+    assert_eq!(
+        task.previous_run_time(now),
+        Some(datetime!(2024-12-26 09:00:00 +8))
+    );
+    assert_eq!(
+        task.previous_run_time(datetime!(2024-12-26 09:00:00 +8)),
+        Some(datetime!(2024-12-26 09:00:00 +8))
+    );
+    assert_eq!(
+        task.previous_run_time(datetime!(2024-12-26 08:00:00 +8)),
+        Some(datetime!(2024-12-25 09:00:00 +8))
+    );
+
+    // 2024-12-26 is a Thursday, skipped
+    let task = Task::At(time!(09:00), Some(vec![Skip::Day(vec![4])]));
+    assert_eq!(
+        task.previous_run_time(now),
+        Some(datetime!(2024-12-25 09:00:00 +8))
+    );
+
+    // Monday and Friday
+    let task = Task::Weekly(vec![1, 5], time!(18:00), None);
+    assert_eq!(
+        task.previous_run_time(now),
+        Some(datetime!(2024-12-23 18:00:00 +8))
+    );
+
+    let task = Task::IntervalPhased(300, datetime!(2024-12-26 00:01:00 +8), None);
+    assert_eq!(
+        task.previous_run_time(datetime!(2024-12-26 10:05:00 +8)),
+        Some(datetime!(2024-12-26 10:01:00 +8))
+    );
+
+    let task = Task::Cron("*/10 * * * * *".to_string(), None);
+    assert_eq!(
+        task.previous_run_time(datetime!(2024-12-26 10:00:25 +8)),
+        Some(datetime!(2024-12-26 10:00:20 +8))
+    );
+
+    let once = datetime!(2024-12-26 09:30:00 +8);
+    assert_eq!(Task::Once(once, None).previous_run_time(now), Some(once));
+    assert_eq!(
+        Task::Once(once, None).previous_run_time(datetime!(2024-12-26 09:00:00 +8)),
+        None
+    );
+
+    assert_eq!(Task::Interval(60, None).previous_run_time(now), None);
+    assert_eq!(Task::Wait(60, None).previous_run_time(now), None);
+}
+
+#[test]
+fn test_task_parse_at_seconds() {
+    assert_eq!(
+        Task::parse("at(14:30)").unwrap(),
+        Task::At(time!(14:30:00), None)
+    );
+    let task = Task::parse("at(14:30:45, weekday 6)").unwrap();
+    assert_eq!(
+        task,
+        Task::At(time!(14:30:45), Some(vec![Skip::Day(vec![6])]))
+    );
+    assert_eq!(task.to_string(), "at(14:30:45, weekday 6)");
+
+    // the time printed back parses to the same task
+    if let Task::At(at, _) = task {
+        let again = Task::parse(&format!(
+            "at({:02}:{:02}:{:02}, weekday 6)",
+            at.hour(),
+            at.minute(),
+            at.second()
+        ))
+        .unwrap();
+        assert_eq!(again, task);
+    }
+
+    assert!(Task::parse("at(14:30:60)").is_err());
+    assert!(Task::parse("at(14)").is_err());
+
+    assert_eq!(task!(at 14:30:45), Task::At(time!(14:30:45), None));
+    assert_eq!(
+        task!(at 14:30:45, weekday 6),
+        Task::At(time!(14:30:45), Some(vec![Skip::Day(vec![6])]))
+    );
+    assert_eq!(task!(at 9:30), Task::At(time!(09:30), None));
+}
+
+#[test]
+fn test_task_at_multi() {
+    let task = Task::parse("at(09:00,13:00, 18:00)").unwrap();
+    assert_eq!(
+        task,
+        Task::AtMulti(vec![time!(09:00), time!(13:00), time!(18:00)], None)
+    );
+
+    // between two of the times
+    let now = datetime!(2024-12-26 10:30:00 +8);
+    let times: Vec<_> = task.run_times(now).take(4).collect();
+    assert_eq!(
+        times,
+        vec![
+            datetime!(2024-12-26 13:00:00 +8),
+            datetime!(2024-12-26 18:00:00 +8),
+            datetime!(2024-12-27 09:00:00 +8),
+            datetime!(2024-12-27 13:00:00 +8),
+        ]
+    );
+    // the order of the times does not matter
+    let unordered = Task::AtMulti(vec![time!(18:00), time!(09:00), time!(13:00)], None);
+    assert_eq!(unordered.run_times(now).next(), times.first().copied());
+
+    let task = Task::parse("at(09:00,18:00, [weekday 4, time 18:00])").unwrap();
+    assert_eq!(
+        task,
+        Task::AtMulti(
+            vec![time!(09:00), time!(18:00)],
+            Some(vec![Skip::Day(vec![4]), Skip::Time(time!(18:00))])
+        )
+    );
+
+    assert!(Task::parse("at(09:00,25:00)").is_err());
+    assert!(Task::parse("at(09:00,13:00 until 2025-01-01)").is_err());
+}
+
+#[test]
+fn test_task_within() {
+    let start = datetime!(2024-12-26 00:00:00 +8);
+    let end = datetime!(2024-12-28 12:00:00 +8);
+    let task = Task::At(time!(09:00), None).within(start, end);
+    assert_eq!(
+        task,
+        Task::Within(Box::new(Task::At(time!(09:00), None)), start, end)
+    );
+
+    // before the window, the first trigger is inside it
+    let times: Vec<_> = task.run_times(datetime!(2024-12-20 10:00:00 +8)).collect();
+    assert_eq!(
+        times,
+        vec![
+            datetime!(2024-12-26 09:00:00 +8),
+            datetime!(2024-12-27 09:00:00 +8),
+            datetime!(2024-12-28 09:00:00 +8),
+        ]
+    );
+    // during the window
+    assert_eq!(
+        task.run_times(datetime!(2024-12-27 10:00:00 +8))
+            .collect::<Vec<_>>(),
+        vec![datetime!(2024-12-28 09:00:00 +8)]
+    );
+    // after the window
+    assert_eq!(
+        task.run_times(datetime!(2024-12-28 10:00:00 +8)).next(),
+        None
+    );
+
+    assert_eq!(
+        task.previous_run_time(datetime!(2024-12-30 10:00:00 +8)),
+        Some(datetime!(2024-12-28 09:00:00 +8))
+    );
+    assert_eq!(
+        task.previous_run_time(datetime!(2024-12-26 08:00:00 +8)),
+        None
+    );
+
+    // the end is excluded
+    let until_nine = Task::At(time!(09:00), None).within(start, datetime!(2024-12-28 09:00:00 +8));
+    assert_eq!(
+        until_nine
+            .run_times(datetime!(2024-12-27 10:00:00 +8))
+            .next(),
+        None
+    );
+    assert_eq!(
+        until_nine.previous_run_time(datetime!(2024-12-30 10:00:00 +8)),
+        Some(datetime!(2024-12-27 09:00:00 +8))
+    );
+
+    // the skips of the wrapped task still apply, 2024-12-26 is a Thursday
+    let task = Task::At(time!(09:00), Some(vec![Skip::Day(vec![4])])).within(start, end);
+    assert_eq!(
+        task.previous_run_time(datetime!(2024-12-26 10:00:00 +8)),
+        None
+    );
+}
+
+#[test]
+fn test_task_to_config_string() {
+    let task = Task::Wait(10, Some(vec![Skip::Day(vec![6, 7])]));
+    assert_eq!(task.to_config_string().unwrap(), "wait(10, weekday 6,7)");
+    assert_eq!(
+        Task::parse(&task.to_config_string().unwrap()).unwrap(),
+        task
+    );
+
+    let tasks = vec![
+        Task::Wait(10, None),
+        Task::WaitMillis(250, None),
+        Task::Interval(60, Some(vec![Skip::TimeRange(time!(22:00), time!(06:00))])),
+        Task::IntervalMillis(1500, Some(vec![Skip::Date(date!(2024 - 12 - 25))])),
+        Task::FixedRate(5, None),
+        Task::At(
+            time!(09:30:15),
+            Some(vec![
+                Skip::DayRange(6, 1),
+                Skip::DateRange(date!(2024 - 12 - 24), date!(2024 - 12 - 26)),
+                Skip::Time(time!(12:00)),
+                Skip::FirstOfMonth,
+                Skip::LastOfMonth,
+            ]),
+        ),
+        Task::AtUntil(time!(09:00), date!(2025 - 01 - 01), None),
+        Task::AtMulti(
+            vec![time!(09:00), time!(18:00)],
+            Some(vec![Skip::OnlyDay(vec![6, 7, 1])]),
+        ),
+        Task::At(
+            time!(09:00),
+            Some(vec![Skip::All(vec![
+                Skip::Day(vec![5]),
+                Skip::OnlyTimeRange(time!(08:00), time!(10:00)),
+            ])]),
+        ),
+        Task::Once(
+            datetime!(2024-12-31 23:59:59 -5),
+            Some(vec![Skip::Day(vec![2])]),
+        ),
+        Task::OnceOn(date!(2024 - 12 - 31), time!(09:00), None),
+        Task::Hourly(15, None),
+        Task::Cron(
+            "0 9,18 * * *".to_string(),
+            Some(vec![Skip::OnlyDay(vec![1, 2, 3, 4, 5])]),
+        ),
+        Task::Wait(
+            10,
+            Some(vec![Skip::Day(vec![6, 7]), Skip::Month(vec![1, 8, 12])]),
+        ),
+        Task::Interval(
+            60,
+            Some(vec![Skip::Any(vec![
+                Skip::Date(date!(2024 - 12 - 25)),
+                Skip::All(vec![Skip::Day(vec![6, 7]), Skip::Time(time!(18:00))]),
+            ])]),
+        ),
+        Task::At(
+            time!(09:00),
+            Some(vec![
+                Skip::Any(vec![Skip::Month(vec![7, 8])]),
+                Skip::FirstOfMonth,
+            ]),
+        ),
+        Task::Once(datetime!(2024-12-31 23:59:59 +5:45), None),
+    ];
+    for task in tasks {
+        let config = task.to_config_string().unwrap();
+        assert_eq!(Task::parse(&config).unwrap(), task, "{config}");
+    }
+
+    // no syntax for these
+    assert_eq!(
+        Task::Weekly(vec![1], time!(09:00), None).to_config_string(),
+        None
+    );
+    assert_eq!(
+        Task::Wait(10, Some(vec![Skip::Not(Box::new(Skip::FirstOfMonth))])).to_config_string(),
+        None
+    );
+    assert_eq!(
+        Task::Once(datetime!(2024-12-31 23:59:59.5 +8), None).to_config_string(),
+        None
+    );
+}
+
+#[test]
+fn test_task_display_round_trip() {
+    assert_eq!(
+        Task::Wait(10, Some(vec![Skip::Date(date!(2024 - 12 - 25))])).to_string(),
+        "wait(10, date 2024-12-25)"
+    );
+    assert_eq!(Task::Interval(30, None).to_string(), "interval(30)");
+    assert_eq!(Task::At(time!(14:30), None).to_string(), "at(14:30:00)");
+    assert_eq!(
+        Task::Once(
+            datetime!(2024-12-31 23:59:59 +8),
+            Some(vec![Skip::Day(vec![6, 7])])
+        )
+        .to_string(),
+        "once(2024-12-31 23:59:59 +08, weekday 6,7)"
+    );
+    assert_eq!(
+        Task::Once(datetime!(2024-12-31 23:59:59 +5:30), None).to_string(),
+        "once(2024-12-31 23:59:59 +05:30)"
+    );
+
+    let skips = [
+        None,
+        Some(vec![Skip::Day(vec![6])]),
+        Some(vec![Skip::TimeRange(time!(22:00), time!(06:00))]),
+        Some(vec![
+            Skip::DateRange(date!(2024 - 12 - 24), date!(2024 - 12 - 26)),
+            Skip::OnlyTimeRange(time!(08:00), time!(18:00)),
+            Skip::MonthRange(11, 2),
+            Skip::LastOfMonth,
+        ]),
+    ];
+    for skip in skips {
+        let tasks = [
+            Task::Wait(10, skip.clone()),
+            Task::WaitMillis(250, skip.clone()),
+            Task::Interval(30, skip.clone()),
+            Task::IntervalMillis(1500, skip.clone()),
+            Task::FixedRate(5, skip.clone()),
+            Task::At(time!(14:30:45), skip.clone()),
+            Task::AtUntil(time!(09:00), date!(2025 - 01 - 01), skip.clone()),
+            Task::AtMulti(vec![time!(09:00), time!(18:00)], skip.clone()),
+            Task::Once(datetime!(2024-12-31 23:59:59 +8), skip.clone()),
+            Task::Once(datetime!(2024-12-31 23:59:59 -3:30), skip.clone()),
+            Task::OnceOn(date!(2024 - 12 - 31), time!(09:00), skip.clone()),
+            Task::Hourly(15, skip.clone()),
+            Task::Cron("0 9,18 * * *".to_string(), skip.clone()),
+        ];
+        for task in tasks {
+            let text = task.to_string();
+            assert_eq!(Task::parse(&text), Ok(task), "{text}");
+        }
+    }
+
+    // tasks without a syntax are described instead
+    assert_eq!(
+        Task::Weekly(vec![1], time!(09:00), None).to_string(),
+        "weekly: [1] 9:00:00.0 "
+    );
+}
+
+#[test]
+fn test_task_display_without_syntax() {
+    let start = datetime!(2025-01-01 00:00 +8);
+    let tasks = vec![
+        Task::IntervalPhased(60, start, None),
+        Task::Weekly(vec![1, 3], time!(09:00), None),
+        Task::BusinessDayInterval(2, time!(09:00), None),
+        Task::At(time!(09:00), None).within(start, start + time::Duration::days(7)),
+        Task::Wait(
+            10,
+            Some(vec![Skip::TimeRangeExclusive(time!(09:00), time!(10:00))]),
+        ),
+        Task::Wait(10, Some(vec![Skip::TimeGrid(time!(00:00), 30)])),
+        Task::Wait(10, Some(vec![Skip::NthWeekday(1, 1)])),
+        Task::Wait(10, Some(vec![Skip::Not(Box::new(Skip::FirstOfMonth))])),
+        Task::Interval(
+            60,
+            Some(vec![Skip::All(vec![Skip::Not(Box::new(Skip::Day(vec![
+                6,
+            ])))])]),
+        ),
+        Task::Once(datetime!(2024-12-31 23:59:59.5 +8), None),
+    ];
+    // no syntax for these, the description is not mistaken for another task
+    for task in tasks {
+        assert_eq!(task.to_config_string(), None, "{task:?}");
+        assert!(Task::parse(&task.to_string()).is_err(), "{task}");
+    }
+}
+
+#[test]
+fn test_task_nth_weekday() {
+    // payroll at 09:00 on the last Friday of the month
+    let task = Task::At(time!(09:00), Some(vec![Skip::NthWeekday(-1, 5)]));
+    assert_eq!(
+        task.previous_run_time(datetime!(2025-01-15 00:00:00 +8)),
+        Some(datetime!(2024-12-27 09:00:00 +8))
+    );
+    assert_eq!(
+        task.previous_run_time(datetime!(2025-02-28 10:00:00 +8)),
+        Some(datetime!(2025-02-28 09:00:00 +8))
+    );
+}
+
+#[test]
+fn test_task_at_offset() {
+    use time::ext::NumericalDuration;
+
+    assert_eq!(
+        Task::at_offset(time!(09:00), -15.minutes()),
+        Task::At(time!(08:45), None)
+    );
+    assert_eq!(
+        Task::at_offset(time!(00:05), -10.minutes()),
+        Task::At(time!(23:55), None)
+    );
+    assert_eq!(
+        Task::at_offset(time!(23:30), 45.minutes()),
+        Task::At(time!(00:15), None)
+    );
+    assert_eq!(
+        Task::at_offset(time!(09:00), 2.days() + 30.seconds()),
+        Task::At(time!(09:00:30), None)
+    );
+}
+
+#[test]
+fn test_collides_within() {
+    let day = std::time::Duration::from_secs(24 * 3600);
+    let report = Task::At(time!(09:00), None);
+    let backup = Task::At(time!(09:00), Some(vec![Skip::Day(vec![6, 7])]));
+
+    let collision = collides_within(&report, &backup, 7 * day, 0).unwrap();
+    assert_eq!(collision.time(), time!(09:00));
+    assert_eq!(collision.offset(), offset!(UTC));
+    assert!(!matches!(collision.weekday().number_from_monday(), 6 | 7));
+
+    let cleanup = Task::At(time!(09:30), None);
+    assert_eq!(collides_within(&report, &cleanup, 7 * day, 0), None);
+    // too short a window to reach the next run
+    assert_eq!(
+        collides_within(&report, &report, std::time::Duration::ZERO, 0),
+        None
+    );
+
+    // a window past the representable range is clamped, and the search still ends
+    let forever = std::time::Duration::MAX;
+    assert!(collides_within(&report, &backup, forever, 0).is_some());
+    assert_eq!(collides_within(&report, &cleanup, forever, 0), None);
+    // a schedule that never advances ends after its first run
+    let stuck = Task::Interval(0, None);
+    assert_eq!(collides_within(&stuck, &cleanup, forever, 0), None);
+    assert_eq!(collides_within(&cleanup, &stuck, 7 * day, 0), None);
+
+    // 20:00 UTC is 04:00 the next day at +8, where the date skip applies
+    let once = datetime!(2030-01-01 20:00 UTC);
+    let skipped = Task::Once(once, Some(vec![Skip::Date(date!(2030 - 01 - 02))]));
+    let plain = Task::Once(once, None);
+    assert_eq!(collides_within(&skipped, &plain, forever, 8 * 60), None);
+    assert_eq!(collides_within(&skipped, &plain, forever, 0), Some(once));
+}
+
+#[test]
+fn test_task_builder() {
+    assert_eq!(
+        Task::interval(300)
+            .skip_days([6, 7])
+            .skip_time_range(time!(22:00), time!(06:00))
+            .build(),
+        Task::Interval(
+            300,
+            Some(vec![
+                Skip::Day(vec![6, 7]),
+                Skip::TimeRange(time!(22:00), time!(06:00))
+            ])
+        )
+    );
+    assert_eq!(Task::wait(10).build(), Task::Wait(10, None));
+    assert_eq!(
+        Task::at(time!(09:30))
+            .skip_day_range(6, 7)
+            .skip_date(date!(2024 - 12 - 25))
+            .skip_time(time!(12:00))
+            .build(),
+        Task::At(
+            time!(09:30),
+            Some(vec![
+                Skip::DayRange(6, 7),
+                Skip::Date(date!(2024 - 12 - 25)),
+                Skip::Time(time!(12:00))
+            ])
+        )
+    );
+    assert_eq!(
+        Task::from(
+            Task::once(datetime!(2024-12-31 23:59:59 +8))
+                .skip_date_range(date!(2024 - 12 - 24), date!(2024 - 12 - 26))
+                .skip(Skip::LastOfMonth)
+        ),
+        Task::Once(
+            datetime!(2024-12-31 23:59:59 +8),
+            Some(vec![
+                Skip::DateRange(date!(2024 - 12 - 24), date!(2024 - 12 - 26)),
+                Skip::LastOfMonth
+            ])
+        )
+    );
+}
+
+#[test]
+fn test_task_timeline() {
+    let task = Task::at(time!(09:00)).skip_days([6, 7]).build();
+    let timeline = task.timeline(7, 8 * 60);
+    let lines = timeline.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 7);
+    for line in &lines {
+        assert!(line.contains(" 09:00:00"), "{line}");
+        let weekend = line.contains(" Sat ") || line.contains(" Sun ");
+        assert_eq!(line.ends_with(" skipped: day: [6, 7]"), weekend, "{line}");
+    }
+    assert_eq!(
+        lines.iter().filter(|line| line.contains("skipped")).count(),
+        2
+    );
+
+    let saturday = datetime!(2025-01-04 09:00 +8);
+    assert_eq!(task.explain_skip(saturday), Some(&Skip::Day(vec![6, 7])));
+    assert_eq!(task.explain_skip(saturday + time::Duration::days(2)), None);
+
+    let past = Task::Once(datetime!(2020-01-01 00:00 +8), None);
+    assert_eq!(
+        past.timeline(3, 8 * 60),
+        "once(2020-01-01 00:00:00 +08) never fires"
+    );
+}
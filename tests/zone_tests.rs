@@ -0,0 +1,112 @@
+#![cfg(feature = "tz")]
+
+use easy_schedule::Tz;
+use easy_schedule::prelude::*;
+use time::macros::{datetime, offset, time};
+
+#[test]
+fn test_at_keeps_local_time_over_spring_forward() {
+    // clocks in London go from 01:00 GMT to 02:00 BST on 2025-03-30
+    let task = Task::At(time!(09:00), None);
+    let now = datetime!(2025-03-28 12:00:00 UTC);
+
+    let times: Vec<_> = task
+        .run_times_in_zone(now, Tz::Europe__London)
+        .take(3)
+        .collect();
+    assert_eq!(
+        times,
+        vec![
+            datetime!(2025-03-29 09:00:00 +0),
+            datetime!(2025-03-30 09:00:00 +1),
+            datetime!(2025-03-31 09:00:00 +1),
+        ]
+    );
+    // a fixed offset drifts to 10:00 local
+    assert_eq!(
+        task.run_times(now).nth(1),
+        Some(datetime!(2025-03-30 09:00:00 +0))
+    );
+
+    // skips still see the local date
+    let task = Task::At(time!(09:00), Some(vec![Skip::Day(vec![7])]));
+    let times: Vec<_> = task
+        .run_times_in_zone(now, Tz::Europe__London)
+        .take(3)
+        .collect();
+    assert_eq!(times[1].weekday(), time::Weekday::Sunday);
+}
+
+#[test]
+fn test_at_in_daylight_saving_gap_and_overlap() {
+    // 01:30 does not exist on 2025-03-30, it moves forward by the hour skipped
+    let task = Task::At(time!(01:30), None);
+    let next = task
+        .run_times_in_zone(datetime!(2025-03-29 12:00:00 UTC), Tz::Europe__London)
+        .next();
+    assert_eq!(next, Some(datetime!(2025-03-30 02:30:00 +1)));
+
+    // 01:30 happens twice on 2025-10-26, the first one is used
+    let next = task
+        .run_times_in_zone(datetime!(2025-10-25 12:00:00 UTC), Tz::Europe__London)
+        .next();
+    assert_eq!(next, Some(datetime!(2025-10-26 01:30:00 +1)));
+}
+
+#[test]
+fn test_interval_ignores_daylight_saving() {
+    let task = Task::Interval(3600, None);
+    let times: Vec<_> = task
+        .run_times_in_zone(datetime!(2025-03-30 00:30:00 UTC), Tz::Europe__London)
+        .take(2)
+        .collect();
+    assert_eq!(times[1] - times[0], time::Duration::HOUR);
+    assert_eq!(times[1], datetime!(2025-03-30 02:30:00 UTC));
+}
+
+#[tokio::test]
+async fn test_with_timezone_name() {
+    let scheduler = Scheduler::with_timezone_name("Europe/London").unwrap();
+    assert!([offset!(+0), offset!(+1)].contains(&scheduler.offset()));
+
+    let scheduler = Scheduler::builder().zone(Tz::Asia__Kolkata).build();
+    assert_eq!(scheduler.offset(), offset!(+5:30));
+
+    assert!(Scheduler::with_timezone_name("Mars/Olympus").is_err());
+}
+
+#[derive(Debug)]
+struct ZoneTask(Task);
+
+#[async_trait]
+impl Notifiable for ZoneTask {
+    fn get_task(&self) -> Task {
+        self.0.clone()
+    }
+
+    async fn on_time(&self, _cancel: CancellationToken) {}
+}
+
+#[tokio::test]
+async fn test_get_next_run_time_in_zone() {
+    let scheduler = Scheduler::builder().zone(Tz::Europe__London).build();
+
+    // a time of the day takes the offset of its own date, not the one of today
+    for (date, offset) in [
+        (time::macros::date!(2030 - 01 - 15), offset!(+0)),
+        (time::macros::date!(2030 - 07 - 15), offset!(+1)),
+    ] {
+        let task = ZoneTask(Task::OnceOn(date, time!(09:00), None));
+        assert_eq!(
+            scheduler.get_next_run_time(task),
+            Some(date.with_time(time!(09:00)).assume_offset(offset))
+        );
+    }
+
+    // the same as the first of the upcoming runs
+    let task = Task::At(time!(09:00), None);
+    assert_eq!(
+        scheduler.get_next_run_time(ZoneTask(task.clone())),
+        scheduler.next_run_times(ZoneTask(task), 1).first().copied()
+    );
+}